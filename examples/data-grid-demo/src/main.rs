@@ -1,6 +1,15 @@
 use yew::prelude::*;
 use yew::Callback;
-use yew_data_grid::data_grid::{GridData, GridDataColumn, DataGrid, GridDataColumnProps};
+use yew_data_grid::data_grid::{GridData, GridDataColumn, DataGrid, GridDataColumnProps, CellContext, CellEdit};
+use yew_data_grid::aggregation::Aggregation;
+use yew_data_grid::editors::CellEditor;
+use yew_data_grid::export::{CellValue, JsonFormat, JsonOptions, download};
+use yew_data_grid::grid_api::use_grid_api;
+use yew_data_grid::import::{CsvImportOptions, ImportedRow};
+use yew_data_grid::row_state::RowTransaction;
+use yew_data_grid::styling::{Condition, Pattern, StyleRule};
+use yew_data_grid::theme::Theme;
+use yew_data_grid::xlsx::{XLSX_MIME_TYPE, XlsxOptions};
 
 const ADD_NUM: usize = 1000;
 
@@ -10,6 +19,7 @@ struct Task {
     pub id: usize,
     pub name: String,
     pub description: String,
+    pub done: bool,
 }
 // column data type
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -17,6 +27,7 @@ enum TaskFields {
     Id,
     Name,
     Description,
+    Done,
 }
 // TODO - create derive macro for this
 impl GridDataColumn for TaskFields {
@@ -27,19 +38,32 @@ impl GridDataColumn for TaskFields {
                 header_name: "Id".to_string(),
                 width: 50,
                 editable: false,
-                sortable: true
+                sortable: true,
+                aggregation: Some(Aggregation::Count),
+                ..Default::default()
             },
             TaskFields::Name => GridDataColumnProps {
                 header_name: "Task Name".to_string(),
                 width: 150,
                 editable: true,
-                sortable: true
+                sortable: true,
+                ..Default::default()
             },
             TaskFields::Description => GridDataColumnProps {
                 header_name: "Description Testing".to_string(),
                 width: 200,
                 editable: true,
-                sortable: true
+                sortable: true,
+                editor: CellEditor::MultilineText,
+                ..Default::default()
+            },
+            TaskFields::Done => GridDataColumnProps {
+                header_name: "Done".to_string(),
+                width: 60,
+                editable: true,
+                sortable: true,
+                editor: CellEditor::Checkbox,
+                ..Default::default()
             },
         }
     }
@@ -48,6 +72,44 @@ impl GridDataColumn for TaskFields {
             TaskFields::Id => row.id.to_string(),
             TaskFields::Name => row.name.to_string(),
             TaskFields::Description => row.description.to_string(),
+            TaskFields::Done => row.done.to_string(),
+        }
+    }
+    fn get_typed_value(&self, row: &Task) -> CellValue {
+        match self {
+            TaskFields::Id => CellValue::Number(row.id as f64),
+            TaskFields::Done => row.done.into(),
+            _ => self.get_value(row).into()
+        }
+    }
+    fn validate_import(&self, value: &str) -> Result<(), String> {
+        match self {
+            TaskFields::Name if value.trim().is_empty() => Err("Task name is required".to_string()),
+            TaskFields::Done if !matches!(value, "true" | "false" | "") => Err("Expected true or false".to_string()),
+            _ => Ok(())
+        }
+    }
+    fn validate(&self, _row: &Task, new_value: &str) -> Result<(), String> {
+        match self {
+            TaskFields::Name if new_value.trim().is_empty() => Err("Task name is required".to_string()),
+            _ => Ok(())
+        }
+    }
+    fn render_cell(&self, row: &Task, ctx: &CellContext<usize>) -> Html {
+        match self {
+            TaskFields::Name if ctx.selected => html! { <b>{&row.name}</b> },
+            _ => html! { self.get_value(row) }
+        }
+    }
+}
+
+impl TaskFields {
+    fn set_value(&self, row: &mut Task, value: String) {
+        match self {
+            TaskFields::Id => {}
+            TaskFields::Name => row.name = value,
+            TaskFields::Description => row.description = value,
+            TaskFields::Done => row.done = value == "true",
         }
     }
 }
@@ -56,8 +118,8 @@ impl GridDataColumn for TaskFields {
 impl GridData for Task {
     type IdType = usize;
     type ColumnType = TaskFields;
-    fn get_id(&self) -> String {
-        self.id.to_string()
+    fn get_id(&self) -> usize {
+        self.id
     }
 }
 
@@ -74,36 +136,107 @@ fn app() -> Html {
                 id: 1,
                 name: "Task 1".to_string(),
                 description: "Task 1 Description".to_string(),
+                done: false,
             },
             Task {
                 id: 2,
                 name: "Task 2".to_string(),
                 description: "Task 2 Description".to_string(),
+                done: false,
             },
             Task {
                 id: 3,
                 name: "Task 3".to_string(),
                 description: "Task 3 Description".to_string(),
+                done: true,
             },
         ]
     });
+    let on_import = {
+        let rows = rows.clone();
+        let last_id = last_id.clone();
+        Callback::from(move |imported: Vec<ImportedRow<TaskFields>>| {
+            let mut new_rows = (*rows).clone();
+            for (n, record) in imported.into_iter().enumerate() {
+                let mut task = Task { id: *last_id + n + 1, name: String::new(), description: String::new(), done: false };
+                for (column, value) in record.values {
+                    column.set_value(&mut task, value);
+                }
+                new_rows.push(task);
+            }
+            last_id.set(new_rows.iter().map(|row| row.id).max().unwrap_or_default());
+            rows.set(new_rows);
+        })
+    };
+    let api = use_grid_api();
     let onclick = {
         let rows = rows.clone();
+        let api = api.clone();
         Callback::from(move |_| {
-            let mut new_rows = (*rows).clone();
+            let mut transaction = RowTransaction::default();
             for n in 1..(ADD_NUM+1) {
                 let id = *last_id + n;
-                new_rows.push(Task {
+                transaction = transaction.add_row(Task {
                     id,
                     name: format!("Task {}", id),
                     description: format!("Task {} Description", id),
+                    done: false,
                 });
             }
+            let mut new_rows = (*rows).clone();
+            api.apply_transaction(&mut new_rows, transaction);
             last_id.set(*last_id + ADD_NUM);
             rows.set(new_rows);
         })
     };
-    let columns: Vec<TaskFields> = vec![TaskFields::Id, TaskFields::Name, TaskFields::Description];
+    let on_edit_batch_commit = {
+        let rows = rows.clone();
+        Callback::from(move |edits: Vec<CellEdit<usize, TaskFields>>| {
+            let mut new_rows = (*rows).clone();
+            for edit in edits {
+                if let Some(row) = new_rows.iter_mut().find(|r| r.get_id() == edit.row_id) {
+                    edit.column.set_value(row, edit.new_value);
+                }
+            }
+            rows.set(new_rows);
+        })
+    };
+    let on_cell_edit_commit = on_edit_batch_commit.reform(|edit| vec![edit]);
+    let undo = {
+        let api = api.clone();
+        Callback::from(move |_| api.undo())
+    };
+    let redo = {
+        let api = api.clone();
+        Callback::from(move |_| api.redo())
+    };
+    let dark = use_state(|| false);
+    let toggle_theme = {
+        let dark = dark.clone();
+        Callback::from(move |_| dark.set(!*dark))
+    };
+    let theme = if *dark { Theme::dark() } else { Theme::light() };
+    let print = {
+        let api = api.clone();
+        Callback::from(move |_| api.print())
+    };
+    let export_xlsx = {
+        let api = api.clone();
+        Callback::from(move |_| {
+            let options = XlsxOptions { sheet_name: "Tasks".to_string(), file_name: "tasks.xlsx".to_string(), ..Default::default() };
+            match api.export_xlsx(options.clone()) {
+                Ok(bytes) => if let Err(e) = download(&options.file_name, XLSX_MIME_TYPE, &bytes) {
+                    log::error!("xlsx download failed: {e:?}");
+                },
+                Err(e) => log::error!("xlsx export failed: {e}")
+            }
+        })
+    };
+    let style_rules = vec![
+        StyleRule::new(TaskFields::Done, Condition::Equals("true".to_string())).style("color: #8c8c8c;").row(),
+        StyleRule::new(TaskFields::Name, Condition::Matches(Pattern::new("^Task \\d*0$").unwrap())).style("font-weight: bold;"),
+    ];
+    let columns: Vec<TaskFields> = vec![TaskFields::Id, TaskFields::Name, TaskFields::Description, TaskFields::Done];
     let height = 400;
     let style = format!("width: 100%; height: {height}px;");
     let rows = (*rows).clone();
@@ -112,8 +245,16 @@ fn app() -> Html {
         // https://yew.rs/docs/next/concepts/basic-web-technologies/css#inline-styles
         <>
             <button {onclick}>{ add_msg }</button>
+            <button onclick={undo}>{ "Undo" }</button>
+            <button onclick={redo}>{ "Redo" }</button>
+            <button onclick={export_xlsx}>{ "Export XLSX" }</button>
+            <button onclick={print}>{ "Print" }</button>
+            <button onclick={toggle_theme}>{ if *dark { "Light theme" } else { "Dark theme" } }</button>
             <div style={style}>
-                <DataGrid<Task, TaskFields> rows={rows} columns={columns} page_size={200}/>
+                <DataGrid<Task, TaskFields> rows={rows} columns={columns} page_size={200} {api} auto_row_height=true theme={Some(theme)} {style_rules}
+                    csv_import={CsvImportOptions::default()} {on_import}
+                    json_export={JsonOptions { format: JsonFormat::Ndjson, file_name: "tasks.ndjson".to_string(), ..Default::default() }}
+                    {on_cell_edit_commit} on_edit_batch_commit={Some(on_edit_batch_commit)}/>
             </div>
         </>
     )
@@ -135,6 +276,7 @@ mod tests {
             id: 1,
             name: "Task 1".to_string(),
             description: "Description 1".to_string(),
+            done: false,
         };
         assert_eq!(TaskFields::Id.get_value(&row_instance), "1");
         assert_eq!(TaskFields::Name.get_value(&row_instance), "Task 1");
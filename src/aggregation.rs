@@ -0,0 +1,127 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
+use crate::data_grid::GridDataColumn;
+use crate::sorting::compare_values;
+
+/// Aggregates the values of every aggregated row into one value
+pub type AggregateFn = Rc<dyn Fn(&[String]) -> String>;
+
+/// Summary of a column's values shown in group rows and the aggregate footer,
+/// declared with `GridDataColumnProps::aggregation`
+#[derive(Clone)]
+pub enum Aggregation {
+    /// Sum of the values that parse as numbers, rounded to two decimals
+    Sum,
+    /// Average of the values that parse as numbers, rounded to two decimals
+    Avg,
+    /// Smallest value in the order columns sort by, numbers before text
+    Min,
+    Max,
+    Count,
+    DistinctCount,
+    Custom(AggregateFn)
+}
+
+impl PartialEq for Aggregation {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Aggregation::Custom(f), Aggregation::Custom(other_f)) => Rc::ptr_eq(f, other_f),
+            (Aggregation::Custom(_), _) | (_, Aggregation::Custom(_)) => false,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other)
+        }
+    }
+}
+
+impl fmt::Debug for Aggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Aggregation::Custom(_) => write!(f, "Custom"),
+            _ => write!(f, "{}", self.label().unwrap_or_default())
+        }
+    }
+}
+
+impl Aggregation {
+    /// Shown before the aggregated value, custom aggregations have none
+    pub fn label(&self) -> Option<&'static str> {
+        match self {
+            Aggregation::Sum => Some("Sum"),
+            Aggregation::Avg => Some("Avg"),
+            Aggregation::Min => Some("Min"),
+            Aggregation::Max => Some("Max"),
+            Aggregation::Count => Some("Count"),
+            Aggregation::DistinctCount => Some("Distinct"),
+            Aggregation::Custom(_) => None
+        }
+    }
+
+    pub fn apply(&self, values: &[String]) -> String {
+        let numbers = || values.iter().filter_map(|v| v.trim().parse::<f64>().ok());
+        match self {
+            Aggregation::Sum => two_decimals(numbers().sum()),
+            Aggregation::Avg => {
+                let (sum, count) = numbers().fold((0.0, 0), |(sum, count), n| (sum + n, count + 1));
+                if count == 0 {
+                    String::new()
+                } else {
+                    two_decimals(sum / count as f64)
+                }
+            }
+            Aggregation::Min => values.iter().min_by(|a, b| compare_values(a, b)).cloned().unwrap_or_default(),
+            Aggregation::Max => values.iter().max_by(|a, b| compare_values(a, b)).cloned().unwrap_or_default(),
+            Aggregation::Count => values.len().to_string(),
+            Aggregation::DistinctCount => values.iter().collect::<HashSet<_>>().len().to_string(),
+            Aggregation::Custom(f) => f(values)
+        }
+    }
+}
+
+/// Rounds away the float noise of sums and averages, e.g. 0.1 + 0.2 is shown as 0.3
+fn two_decimals(n: f64) -> String {
+    // adding zero turns the -0 of an empty sum or a tiny negative number into 0
+    ((n * 100.0).round() / 100.0 + 0.0).to_string()
+}
+
+/// The column's aggregation over the given rows with its label, `None` when the column has no aggregation
+pub fn aggregate_column<I, T, U>(ids: &[I], rows: &[T], row_index_map: &HashMap<I, usize>, column: &U) -> Option<String>
+    where I: Hash + Eq, U: GridDataColumn<RowType=T>
+{
+    let aggregation = column.get_config().aggregation?;
+    let values: Vec<String> = ids.iter().map(|id| column.get_value(&rows[row_index_map[id]])).collect();
+    let value = aggregation.apply(&values);
+    Some(match aggregation.label() {
+        Some(label) => format!("{label}: {value}"),
+        None => value
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_aggregations() {
+        let numbers = values(&["10", "2", "x", "2.5"]);
+        assert_eq!(Aggregation::Sum.apply(&numbers), "14.5");
+        assert_eq!(Aggregation::Sum.apply(&values(&["0.1", "0.2"])), "0.3");
+        assert_eq!(Aggregation::Sum.apply(&values(&["x", "-0.001"])), "0");
+        assert_eq!(Aggregation::Avg.apply(&values(&["1", "2", "2"])), "1.67");
+        assert_eq!(Aggregation::Avg.apply(&values(&["x"])), "");
+        assert_eq!(Aggregation::Min.apply(&numbers), "2");
+        assert_eq!(Aggregation::Max.apply(&values(&["10", "2", "2.5"])), "10");
+        assert_eq!(Aggregation::Max.apply(&numbers), "x");
+        assert_eq!(Aggregation::Min.apply(&values(&["NaN", "inf", "b", "-inf", "1x"])), "-inf");
+        assert_eq!(Aggregation::Max.apply(&values(&["2", "NaN", "10", "inf"])), "NaN");
+        assert_eq!(Aggregation::Count.apply(&numbers), "4");
+        assert_eq!(Aggregation::DistinctCount.apply(&values(&["a", "b", "a"])), "2");
+        let longest = Aggregation::Custom(Rc::new(|values: &[String]| values.iter().map(|v| v.len()).max().unwrap_or(0).to_string()));
+        assert_eq!(longest.apply(&values(&["a", "abc"])), "3");
+        assert_ne!(longest, Aggregation::Custom(Rc::new(|_: &[String]| String::new())));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::data_grid::{CellEdit, GridDataColumn};
+
+/// Splits tab separated clipboard text, as copied from a spreadsheet, into rows of cells
+pub fn parse_clipboard_text(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    if text.is_empty() {
+        return Vec::new();
+    }
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .map(|line| line.split('\t').map(|cell| cell.to_string()).collect())
+        .collect()
+}
+
+/// Edits for pasting a block of values into `view_rows` with its top left corner on the anchor cell.
+/// Values that fall outside the grid, on read only columns, are unchanged or fail validation are skipped.
+pub fn paste_edits<I, T, U>(view_rows: &[(&I, &T)],
+                            columns: &[U],
+                            anchor: (&I, U),
+                            values: &[Vec<String>]) -> Vec<CellEdit<I, U>>
+    where I: Clone + PartialEq, U: GridDataColumn<RowType=T> + Copy + PartialEq
+{
+    let (anchor_id, anchor_column) = anchor;
+    let (start_row, start_col) = match (view_rows.iter().position(|(id, _)| *id == anchor_id),
+                                        columns.iter().position(|c| *c == anchor_column)) {
+        (Some(row), Some(col)) => (row, col),
+        _ => return Vec::new()
+    };
+    let mut edits = Vec::new();
+    for ((row_id, row), line) in view_rows[start_row..].iter().zip(values) {
+        for (column, new_value) in columns[start_col..].iter().zip(line) {
+            let old_value = column.get_value(row);
+            if !column.get_config().editable || old_value == *new_value || column.validate(row, new_value).is_err() {
+                continue;
+            }
+            edits.push(CellEdit { row_id: (*row_id).clone(), column: *column, old_value, new_value: new_value.clone() });
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{person, Field};
+
+    #[test]
+    fn test_parse_clipboard_text() {
+        assert_eq!(parse_clipboard_text("a\tb\r\nc\td\r\n"), vec![vec!["a", "b"], vec!["c", "d"]]);
+        assert_eq!(parse_clipboard_text("single"), vec![vec!["single"]]);
+        assert!(parse_clipboard_text("").is_empty());
+    }
+
+    #[test]
+    fn test_paste_edits() {
+        let grace = person(2, "Grace").age("85");
+        let ada = person(1, "Ada").age("36");
+        let view_rows = vec![(&2, &grace), (&1, &ada)];
+        let columns = vec![Field::Id, Field::Name, Field::Age];
+        let values = parse_clipboard_text("Hopper\tx\textra\nAda\t40\nignored\t1");
+
+        let edits = paste_edits(&view_rows, &columns, (&2, Field::Name), &values);
+        // invalid age and the unchanged name are skipped, extra columns and rows fall off the grid
+        assert_eq!(edits, vec![
+            CellEdit { row_id: 2, column: Field::Name, old_value: "Grace".to_string(), new_value: "Hopper".to_string() },
+            CellEdit { row_id: 1, column: Field::Age, old_value: "36".to_string(), new_value: "40".to_string() },
+        ]);
+
+        // read only columns are never pasted into
+        let edits = paste_edits(&view_rows, &columns, (&1, Field::Id), &parse_clipboard_text("9\tAlan"));
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].column, Field::Name);
+    }
+}
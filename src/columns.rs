@@ -0,0 +1,100 @@
+/// User changes to the column layout made from the header menu
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnState<U> {
+    pub hidden: Vec<U>,
+    pub pinned: Vec<U>,
+    pub widths: Vec<(U, i32)>
+}
+
+impl<U> Default for ColumnState<U> {
+    fn default() -> Self {
+        Self { hidden: Vec::new(), pinned: Vec::new(), widths: Vec::new() }
+    }
+}
+
+impl<U: Copy + PartialEq> ColumnState<U> {
+    /// Columns in display order, pinned columns first in the order they were pinned
+    pub fn visible_columns(&self, columns: &[U]) -> Vec<U> {
+        let pinned = self.pinned.iter().filter(|c| columns.contains(c));
+        let unpinned = columns.iter().filter(|c| !self.pinned.contains(c));
+        pinned.chain(unpinned)
+            .filter(|c| !self.hidden.contains(c))
+            .copied()
+            .collect()
+    }
+
+    pub fn width(&self, column: &U, default_width: i32) -> i32 {
+        self.widths.iter()
+            .find(|(c, _)| c == column)
+            .map(|(_, width)| *width)
+            .unwrap_or(default_width)
+    }
+
+    pub fn is_pinned(&self, column: &U) -> bool {
+        self.pinned.contains(column)
+    }
+
+    pub fn hide(&mut self, column: U) {
+        if !self.hidden.contains(&column) {
+            self.hidden.push(column);
+        }
+    }
+
+    pub fn toggle_pinned(&mut self, column: U) {
+        match self.pinned.iter().position(|c| *c == column) {
+            Some(i) => { self.pinned.remove(i); }
+            None => self.pinned.push(column)
+        }
+    }
+
+    pub fn set_width(&mut self, column: U, width: i32) {
+        self.widths.retain(|(c, _)| *c != column);
+        self.widths.push((column, width));
+    }
+}
+
+const AUTOSIZE_CHAR_WIDTH: i32 = 8;
+const AUTOSIZE_MIN_WIDTH: i32 = 40;
+
+/// Estimates a width that fits the header and the longest value without measuring the DOM
+pub fn autosize_width<'a>(header_name: &str, values: impl Iterator<Item=&'a str>) -> i32 {
+    let longest = values.map(|v| v.chars().count())
+        .chain(std::iter::once(header_name.chars().count()))
+        .max()
+        .unwrap_or(0) as i32;
+    // header menu button takes about two characters
+    ((longest + 2) * AUTOSIZE_CHAR_WIDTH).max(AUTOSIZE_MIN_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_columns() {
+        let columns = vec![1, 2, 3, 4];
+        let mut state = ColumnState::default();
+        assert_eq!(state.visible_columns(&columns), columns);
+        state.toggle_pinned(3);
+        state.hide(2);
+        assert_eq!(state.visible_columns(&columns), vec![3, 1, 4]);
+        state.toggle_pinned(3);
+        assert_eq!(state.visible_columns(&columns), vec![1, 3, 4]);
+    }
+
+    #[test]
+    fn test_column_width() {
+        let mut state = ColumnState::default();
+        assert_eq!(state.width(&1, 150), 150);
+        state.set_width(1, 80);
+        state.set_width(1, 90);
+        assert_eq!(state.width(&1, 150), 90);
+        assert_eq!(state.widths.len(), 1);
+    }
+
+    #[test]
+    fn test_autosize_width() {
+        assert_eq!(autosize_width("Id", ["1", "22", "333"].into_iter()), 40);
+        assert_eq!(autosize_width("Name", ["a much longer value"].into_iter()), (19 + 2) * 8);
+    }
+}
@@ -1,32 +1,215 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::rc::Rc;
 use instant::{Instant as InstantWeb};
+use wasm_bindgen::JsCast;
+use web_sys::{ClipboardEvent, Element, HtmlElement, HtmlInputElement};
+use crate::aggregation::{Aggregation, aggregate_column};
+use crate::clipboard::{paste_edits, parse_clipboard_text};
+use crate::columns::{ColumnState, autosize_width};
+use crate::editors::CellEditor;
+use crate::error::GridError;
+use crate::export::{CellValue, CsvOptions, ExportView, JsonOptions, download};
+use crate::filtering::{ColumnFilter, filter_row_ids};
+use crate::grid_csv_import::GridCsvImport;
+use crate::import::{CsvImportOptions, ImportedRow};
+use crate::grouping::{GroupExpansion, GroupedRow, group_rows};
 use crate::hooks::pagination::{Pagination, use_page_view};
+use crate::row_editing::{RowEditing, validate_changes};
+use crate::row_height::{Density, RowHeights};
+use crate::row_state::RowDiff;
+use crate::grid_api::{GridApi, GridApiHandlers};
+use crate::grid_cell_editor::GridCellEditor;
+use crate::history::{EditHistory, EditTransaction};
+use crate::grid_header_menu::{GridHeaderMenu, HeaderMenuItem};
 use crate::grid_pagination_bar::{GridPaginationBar};
+use crate::sorting::{SortDirection, SortModel, sort_row_ids};
+use crate::stylesheet::{ClassPrefix, use_stylesheet};
+use crate::styling::{StyleRule, cell_rule, row_rule};
+use crate::theme::Theme;
+use crate::tree::{TreeDataMode, TreeNode, tree_rows};
+use yew::platform::spawn_local;
 use yew::prelude::*;
 
-const DATA_GRID_STYLE: &'static str = include_str!("data_grid.rs.css");
+const DATA_GRID_STYLE: &str = include_str!("data_grid.rs.css");
 
-pub struct RowState {
-    pub row_index_map: HashMap<String, usize>,
-    pub sort_order: Vec<String>
-}
+pub use crate::row_state::{DuplicateIdPolicy, RowState, Rows};
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props<T: GridData<ColumnType=U> + PartialEq, U: GridDataColumn<RowType=T> + PartialEq + Copy> {
-    pub rows: Vec<T>,
+    pub rows: Rows<T>,
     pub columns: Vec<U>,
-    pub page_size: i32
+    pub page_size: i32,
+    /// Controlled page number, when set the grid only reports page changes through `on_page_change`
+    #[prop_or_default]
+    pub page: Option<i32>,
+    #[prop_or_default]
+    pub on_page_change: Callback<i32>,
+    /// Go back to the first page whenever rows are added instead of keeping the current page
+    #[prop_or_default]
+    pub reset_page_on_rows_change: bool,
+    /// Ids of the selected rows, emitted whenever a row is clicked
+    #[prop_or_default]
+    pub on_selection_change: Callback<Vec<T::IdType>>,
+    /// Extra entries appended to the built-in header menu of a column
+    #[prop_or_default]
+    pub header_menu_items: Option<Callback<U, Vec<HeaderMenuItem>>>,
+    /// Called when an edited cell's value changes, the grid does not modify `rows` itself
+    #[prop_or_default]
+    pub on_cell_edit_commit: Callback<CellEdit<T::IdType, U>>,
+    #[prop_or_default]
+    pub edit_mode: EditMode,
+    /// Called when a row edited in `EditMode::Row` is saved, only changed columns are included
+    #[prop_or_default]
+    pub on_row_edit_commit: Callback<RowEdit<T::IdType, U>>,
+    /// Receives the cells changed together by paste, undo and redo, when not set each change goes to `on_cell_edit_commit`.
+    /// Undo and redo of a row saved in row edit mode go to `on_row_edit_commit` instead.
+    #[prop_or_default]
+    pub on_edit_batch_commit: Option<Callback<EditBatch<T::IdType, U>>>,
+    #[prop_or_default]
+    pub api: Option<GridApi<T::IdType, U>>,
+    /// Controlled grouping columns, outermost first, when set the grid only reports changes through `on_group_by_change`
+    #[prop_or_default]
+    pub group_by: Option<Vec<U>>,
+    #[prop_or_default]
+    pub on_group_by_change: Callback<Vec<U>>,
+    /// Shows the rows as a tree, `group_by` is ignored while it is set
+    #[prop_or_default]
+    pub tree_data: Option<TreeDataMode<T>>,
+    /// Called when a row with unloaded children is expanded, add the children to `rows` to show them
+    #[prop_or_default]
+    pub on_load_children: Callback<T::IdType>,
+    /// Content of the panel shown below an expanded row, rows get an expand toggle when this is set
+    #[prop_or_default]
+    pub render_detail: Option<RowFn<T, Html>>,
+    /// Controlled ids of the rows with an open detail panel, when set the grid only reports changes through `on_expanded_change`
+    #[prop_or_default]
+    pub expanded_ids: Option<Vec<T::IdType>>,
+    #[prop_or_default]
+    pub on_expanded_change: Callback<Vec<T::IdType>>,
+    /// Shows a toolbar with a button that downloads the grid as CSV with these options
+    #[prop_or_default]
+    pub csv_export: Option<CsvOptions>,
+    /// Adds a toolbar button that downloads the grid as JSON or NDJSON with these options
+    #[prop_or_default]
+    pub json_export: Option<JsonOptions>,
+    /// Adds a toolbar button that opens a dialog for importing a CSV file, the records go to `on_import`
+    #[prop_or_default]
+    pub csv_import: Option<CsvImportOptions>,
+    #[prop_or_default]
+    pub on_import: Callback<Vec<ImportedRow<U>>>,
+    /// Extra classes of each data row, e.g. to colour overdue rows
+    #[prop_or_default]
+    pub get_row_class: Option<RowFn<T, Classes>>,
+    /// Declarative classes and styles for cells and rows whose values meet a condition
+    #[prop_or_default]
+    pub style_rules: Vec<StyleRule<U>>,
+    /// Colours, fonts and spacing, set as CSS custom properties on the container
+    #[prop_or_default]
+    pub theme: Option<Theme>,
+    /// Set to false to leave out the bundled stylesheet and style the grid's classes yourself
+    #[prop_or(true)]
+    pub bundled_stylesheet: bool,
+    /// Put before every class name of the grid and its stylesheet, e.g. `v2-` for `v2-yew-data-grid-row`
+    #[prop_or_default]
+    pub class_prefix: Option<String>,
+    /// Renders every filtered row on a single page for printing, without the toolbar and pagination bar
+    #[prop_or_default]
+    pub print_mode: bool,
+    #[prop_or_default]
+    pub density: Density,
+    /// Height of every row in pixels, overrides `density`
+    #[prop_or_default]
+    pub row_height: Option<i32>,
+    /// Height of a single row, rows it returns `None` for use the grid's row height
+    #[prop_or_default]
+    pub get_row_height: Option<RowFn<T, Option<i32>>>,
+    /// Rows grow to fit wrapped content instead of cutting it off, the row height becomes the minimum
+    #[prop_or_default]
+    pub auto_row_height: bool,
+    #[prop_or_default]
+    pub duplicate_id_policy: DuplicateIdPolicy,
+    /// Called once for each new problem found in the props, e.g. rows sharing an id
+    #[prop_or_default]
+    pub on_error: Callback<GridError>
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum EditMode {
+    /// Double clicking a cell edits that cell, the change is committed on its own
+    #[default]
+    Cell,
+    /// Double clicking a cell edits every editable cell of the row until it is saved or cancelled
+    Row
+}
+
+/// A committed change to a single cell
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellEdit<I, U> {
+    pub row_id: I,
+    pub column: U,
+    pub old_value: String,
+    pub new_value: String
+}
+
+/// Cells changed together by one paste, or by undoing or redoing a cell edit or a paste
+pub type EditBatch<I, U> = Vec<CellEdit<I, U>>;
+
+/// Changes made to a row in row edit mode
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowEdit<I, U> {
+    pub row_id: I,
+    pub changes: Vec<CellEdit<I, U>>
+}
+
+/// A function of a row passed as a prop, e.g. `get_row_height`. The grid calls it with a reference so rows are
+/// never copied, and like a `Callback` it is compared by pointer.
+pub struct RowFn<T, R>(Rc<dyn Fn(&T) -> R>);
+
+impl<T, R> RowFn<T, R> {
+    pub fn new(f: impl Fn(&T) -> R + 'static) -> Self {
+        Self(Rc::new(f))
+    }
+
+    pub fn call(&self, row: &T) -> R {
+        (self.0)(row)
+    }
+}
+
+impl<T, R, F: Fn(&T) -> R + 'static> From<F> for RowFn<T, R> {
+    fn from(f: F) -> Self {
+        Self::new(f)
+    }
+}
+
+impl<T, R> Clone for RowFn<T, R> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T, R> PartialEq for RowFn<T, R> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 #[function_component(DataGrid)]
-pub fn data_grid<T: GridData<ColumnType=U> + PartialEq,
-                 U: GridDataColumn<RowType=T> + PartialEq + Copy>
+pub fn data_grid<T: GridData<ColumnType=U> + PartialEq + Clone + 'static,
+                 U: GridDataColumn<RowType=T> + PartialEq + Copy + 'static>
                 (props: &Props<T, U>) -> Html {
     // TODO conditional compilation of this effect
-    let _force_update = use_state(|| InstantWeb::now());
-    let pg = use_state(|| Pagination::new(props.rows.len(), props.page_size));
+    let _force_update = use_state(InstantWeb::now);
+    let page = use_state(|| props.page.unwrap_or(1));
+    let print_requested = use_state(|| false);
+    let print_mode = props.print_mode || *print_requested;
 
-    let start = use_mut_ref(|| InstantWeb::now());
+    let start = use_mut_ref(InstantWeb::now);
     start.replace(InstantWeb::now());
     use_effect(move || {
         let render_end = InstantWeb::now();
@@ -36,116 +219,1392 @@ pub fn data_grid<T: GridData<ColumnType=U> + PartialEq,
         || {}
     });
 
-    let row_state = use_mut_ref(|| {
-        let mut row_index_map = HashMap::new();
-        let mut sort_order = Vec::new();
-        for (i, row) in props.rows.iter().enumerate() {
-            row_index_map.insert(row.get_id(), i);
-            sort_order.push(row.get_id());
+    let prefix = ClassPrefix(props.class_prefix.clone().unwrap_or_default());
+    use_stylesheet(DATA_GRID_STYLE, prefix.0.clone(), props.bundled_stylesheet);
+
+    let row_state = use_mut_ref(|| RowState::from_rows(&props.rows, props.duplicate_id_policy));
+    let refresh_requested = use_mut_ref(|| false);
+    if refresh_requested.replace(false) {
+        row_state.replace(RowState::from_rows(&props.rows, props.duplicate_id_policy));
+    }
+
+    // the rows the index was last brought up to date with, and the ids of the transactions applied to them
+    // through the api since, which are only used once the rows they produced arrive
+    let synced_rows = use_mut_ref(|| props.rows.clone());
+    let transactions = use_mut_ref(Vec::<RowDiff<T::IdType>>::new);
+    let rows_changed = !synced_rows.borrow().ptr_eq(&props.rows);
+    let row_diff = if rows_changed || row_state.borrow().policy() != props.duplicate_id_policy {
+        synced_rows.replace(props.rows.clone());
+        let transactions = if rows_changed { transactions.take() } else { Vec::new() };
+        row_state.borrow_mut().sync_transactions(&props.rows, props.duplicate_id_policy, &transactions)
+    } else {
+        None
+    };
+    let reported_error = use_mut_ref(|| None::<GridError>);
+    let grid_error = row_state.borrow().error.clone();
+    if *reported_error.borrow() != grid_error {
+        if let Some(error) = &grid_error {
+            log::error!("{error}");
+            props.on_error.emit(error.clone());
         }
-        RowState { row_index_map, sort_order }
+        reported_error.replace(grid_error.clone());
+    }
+    // with the other policies the duplicates are resolved and the rows can still be shown
+    let error_overlay = grid_error
+        .filter(|_| props.duplicate_id_policy == DuplicateIdPolicy::Reject)
+        .map(|error| html! {
+            <div class={prefix.class("yew-data-grid-error-overlay")} role="alert">{error.to_string()}</div>
+        });
+    let reset_page = props.reset_page_on_rows_change && row_diff.as_ref().is_some_and(|diff| !diff.added.is_empty());
+
+    let sort = use_state(|| None::<SortModel<U>>);
+    let filters = use_state(Vec::<ColumnFilter<U>>::new);
+    let column_state = use_state(ColumnState::<U>::default);
+    let open_menu = use_state(|| None::<U>);
+    let filter_editor = use_state(|| None::<U>);
+
+    let sorted_ids = {
+        let state = row_state.borrow();
+        let mut ids = state.sort_order.clone();
+        if let Some(sort) = *sort {
+            sort_row_ids(&mut ids, &props.rows, &state.row_index_map, &sort);
+        }
+        Rc::new(ids)
+    };
+    let view_ids = {
+        let state = row_state.borrow();
+        Rc::new(filter_row_ids(&sorted_ids, &props.rows, &state.row_index_map, &filters))
+    };
+
+    let group_by_state = use_state(Vec::<U>::new);
+    let group_by = props.group_by.clone().unwrap_or_else(|| (*group_by_state).clone());
+    let on_group_by_change = {
+        let group_by_state = group_by_state.clone();
+        let controlled = props.group_by.is_some();
+        let on_group_by_change = props.on_group_by_change.clone();
+        Callback::from(move |group_by: Vec<U>| {
+            if !controlled {
+                group_by_state.set(group_by.clone());
+            }
+            on_group_by_change.emit(group_by);
+        })
+    };
+    let group_expansion = use_state(GroupExpansion::default);
+    // group headers and the rows of expanded groups, this is what gets paged
+    let tree_expanded = use_state(HashSet::<T::IdType>::new);
+    let tree_nodes = props.tree_data.as_ref().map(|mode| {
+        let state = row_state.borrow();
+        let filtering = filters.iter().any(|f| !f.value.is_empty());
+        tree_rows(&sorted_ids, &view_ids, &props.rows, &state.row_index_map, mode, &tree_expanded, filtering)
     });
+    let visible_rows = {
+        let state = row_state.borrow();
+        Rc::new(match &tree_nodes {
+            Some(tree_nodes) => tree_nodes.iter().map(|(id, _)| GroupedRow::Row(id.clone())).collect(),
+            None => group_rows(&view_ids, &props.rows, &state.row_index_map, &group_by, &group_expansion)
+        })
+    };
+    let tree_nodes: HashMap<T::IdType, TreeNode> = tree_nodes.unwrap_or_default().into_iter().collect();
+    let toggle_tree_row = {
+        let tree_expanded = tree_expanded.clone();
+        let on_load_children = props.on_load_children.clone();
+        Callback::from(move |(id, node): (T::IdType, TreeNode)| {
+            let mut expanded = (*tree_expanded).clone();
+            if !expanded.remove(&id) {
+                expanded.insert(id.clone());
+                if !node.has_children {
+                    on_load_children.emit(id);
+                }
+            }
+            tree_expanded.set(expanded);
+        })
+    };
 
-    if props.rows.len() != row_state.borrow().sort_order.len() {
-        let new_rows = props.rows.iter().filter(|r| !row_state.borrow().row_index_map.contains_key(&r.get_id())).collect::<Vec<&T>>();
-        if row_state.borrow().sort_order.len() + new_rows.len() != props.rows.len() {
-            log::error!("duplicate keys in rows detected cannot render data grid");
-        } else {
-            let mut new_sort_order = row_state.borrow().sort_order.clone();
-            for row in new_rows.iter() {
-                new_sort_order.push(row.get_id());
+    // pagination is derived from the visible rows, only the page number is kept as state
+    let current_page = props.page.unwrap_or(*page);
+    let pagination = if print_mode {
+        // a single page holding every row
+        Pagination::new(visible_rows.len(), (visible_rows.len() as i32).max(1))
+    } else {
+        let shown_page = if reset_page { 1 } else { current_page };
+        Pagination::new(visible_rows.len(), props.page_size).with_page(shown_page)
+    };
+    let on_page_change = {
+        let page = page.clone();
+        let controlled = props.page.is_some();
+        let on_page_change = props.on_page_change.clone();
+        Callback::from(move |new_page: i32| {
+            if !controlled {
+                page.set(new_page);
             }
-            let last_index = row_state.borrow().row_index_map.len();
-            let new_row_indexes: HashMap<String, usize> = new_rows.iter().enumerate().map(|(i, r)| (r.get_id(), i + last_index)).collect();
-            let mut current_row_indexes = row_state.borrow().row_index_map.clone();
-            current_row_indexes.extend(new_row_indexes);
-            row_state.replace(RowState { row_index_map: current_row_indexes, sort_order: new_sort_order });
-            pg.set(Pagination::new(props.rows.len(), props.page_size));
-        }
+            on_page_change.emit(new_page);
+        })
+    };
+    // report the page shown instead of the current one after a reset, or when the rows no longer reach it.
+    // A controlled page only moves once the parent passes the new page back.
+    {
+        let on_page_change = on_page_change.clone();
+        use_effect_with_deps(move |(shown_page, current_page, print_mode)| {
+            if !print_mode && shown_page != current_page {
+                on_page_change.emit(*shown_page);
+            }
+            || {}
+        }, (pagination.page, current_page, print_mode));
     }
+    let page_view = use_page_view(pagination, visible_rows.clone());
+    let page_ids: Vec<T::IdType> = page_view.iter()
+        .filter_map(|row| match row {
+            GroupedRow::Row(id) => Some(id.clone()),
+            GroupedRow::Group { .. } => None
+        })
+        .collect();
+    let visible_columns = column_state.visible_columns(&props.columns);
+    let base_row_height = props.row_height.unwrap_or_else(|| props.density.row_height());
+    let row_height_of = |row: &T| props.get_row_height.as_ref()
+        .and_then(|get_row_height| get_row_height.call(row))
+        .unwrap_or(base_row_height);
+    // heights rendered in auto height mode, by row id
+    let measured_heights = use_mut_ref(HashMap::<T::IdType, i32>::new);
+    // heights of the expanded detail panels, which sit between their row and the next one
+    let detail_heights = use_mut_ref(HashMap::<T::IdType, i32>::new);
 
     // let total_width = props.columns.iter().fold(0, |acc, column| {
     //     let config = column.get_config();
     //     acc + config.width
     // });
 
-    let columns = props.columns.iter().map(|column| {
+    let columns = visible_columns.iter().enumerate().map(|(col_index, column)| {
+        let column = *column;
         let config = column.get_config();
-        let header_name = config.header_name;
-        let width = config.width;
+        let width = column_state.width(&column, config.width);
         let style = format!("width: {width}px");
+        let sort_direction = sort.filter(|s| s.column == column).map(|s| s.direction);
+        let filter_value = filters.iter().find(|f| f.column == column).map(|f| f.value.clone());
+        let pinned = column_state.is_pinned(&column);
+        let ctx = HeaderContext {
+            col_index,
+            sort: sort_direction,
+            filtered: filter_value.as_ref().is_some_and(|v| !v.is_empty()),
+            pinned
+        };
+        let content = column.render_header(&ctx);
+
+        let filter_input = if *filter_editor == Some(column) || ctx.filtered {
+            let oninput = {
+                let filters = filters.clone();
+                let on_page_change = on_page_change.clone();
+                Callback::from(move |e: InputEvent| {
+                    let input: HtmlInputElement = e.target_unchecked_into();
+                    let mut new_filters = (*filters).clone();
+                    new_filters.retain(|f| f.column != column);
+                    new_filters.push(ColumnFilter { column, value: input.value() });
+                    filters.set(new_filters);
+                    on_page_change.emit(1);
+                })
+            };
+            let onblur = {
+                let filter_editor = filter_editor.clone();
+                Callback::from(move |_| filter_editor.set(None))
+            };
+            html! {
+                <input class={prefix.class("yew-data-grid-header-filter-input")} placeholder="Filter"
+                    value={filter_value.unwrap_or_default()} {oninput} {onblur}/>
+            }
+        } else {
+            html! {}
+        };
+
+        let menu = if *open_menu == Some(column) {
+            let page_values = page_ids.iter()
+                .map(|id| column.get_value(&props.rows[row_state.borrow().row_index_map[id]]))
+                .collect::<Vec<String>>();
+            let autosize = autosize_width(&config.header_name, page_values.iter().map(|v| v.as_str()));
+            let mut items = column_menu_items(column, &config, autosize, &sort, &column_state, visible_columns.len(), &filter_editor);
+            items.extend(group_menu_items(column, &group_by, &on_group_by_change, &group_expansion));
+            if let Some(header_menu_items) = &props.header_menu_items {
+                items.extend(header_menu_items.emit(column));
+            }
+            let on_close = {
+                let open_menu = open_menu.clone();
+                Callback::from(move |_| open_menu.set(None))
+            };
+            html! { <GridHeaderMenu {items} {on_close}/> }
+        } else {
+            html! {}
+        };
+        let toggle_menu = {
+            let open_menu = open_menu.clone();
+            Callback::from(move |_| {
+                let next = if *open_menu == Some(column) { None } else { Some(column) };
+                open_menu.set(next);
+            })
+        };
+        let header_class = classes!(prefix.class("yew-data-grid-header-cell"), pinned.then(|| prefix.class("yew-data-grid-header-cell-pinned")));
         html! {
-            <div class="yew-data-grid-header-cell" style={style}>{header_name}</div>
+            <div class={header_class} style={style}>
+                <div class={prefix.class("yew-data-grid-header-cell-content")}>{content}</div>
+                {filter_input}
+                <button class={prefix.class("yew-data-grid-header-menu-button")} onclick={toggle_menu}>{"\u{22ee}"}</button>
+                {menu}
+            </div>
         }
     }).collect::<Html>();
 
+    let selection = use_state(Vec::<T::IdType>::new);
+    let on_row_click = {
+        let selection = selection.clone();
+        let on_selection_change = props.on_selection_change.clone();
+        Callback::from(move |(row_id, e): (T::IdType, MouseEvent)| {
+            let mut selected = (*selection).clone();
+            if e.ctrl_key() || e.meta_key() {
+                match selected.iter().position(|id| *id == row_id) {
+                    Some(i) => { selected.remove(i); }
+                    None => selected.push(row_id)
+                }
+            } else {
+                selected = vec![row_id];
+            }
+            on_selection_change.emit(selected.clone());
+            selection.set(selected);
+        })
+    };
+
+    let editing = use_state(|| None::<EditingCell<T::IdType, U>>);
+    // bumped whenever editing starts or is cancelled so late async validation results are dropped
+    let edit_session = use_mut_ref(|| 0u32);
+    // row edit drafts are read by blur handlers that may run before the next render, so they live in a ref
+    let row_editing = use_mut_ref(|| None::<RowEditing<T::IdType, U>>);
+    let force_update = use_force_update();
+    let start_editing = {
+        let editing = editing.clone();
+        let edit_session = edit_session.clone();
+        let row_editing = row_editing.clone();
+        let force_update = force_update.clone();
+        let edit_mode = props.edit_mode;
+        Callback::from(move |(row_id, column): (T::IdType, U)| {
+            match edit_mode {
+                EditMode::Cell => {
+                    *edit_session.borrow_mut() += 1;
+                    editing.set(Some(EditingCell::new(row_id, column)));
+                }
+                EditMode::Row => {
+                    if row_editing.borrow().as_ref().is_some_and(|e| e.row_id == row_id) {
+                        return;
+                    }
+                    *edit_session.borrow_mut() += 1;
+                    *row_editing.borrow_mut() = Some(RowEditing::new(row_id, column));
+                    force_update.force_update();
+                }
+            }
+        })
+    };
+    let expanded_state = use_state(Vec::<T::IdType>::new);
+    let expanded_ids = props.expanded_ids.clone().unwrap_or_else(|| (*expanded_state).clone());
+    let toggle_detail = {
+        let expanded_state = expanded_state.clone();
+        let controlled = props.expanded_ids.is_some();
+        let on_expanded_change = props.on_expanded_change.clone();
+        let expanded_ids = expanded_ids.clone();
+        Callback::from(move |id: T::IdType| {
+            let mut new_ids = expanded_ids.clone();
+            match new_ids.iter().position(|expanded| *expanded == id) {
+                Some(i) => { new_ids.remove(i); }
+                None => new_ids.push(id)
+            }
+            if !controlled {
+                expanded_state.set(new_ids.clone());
+            }
+            on_expanded_change.emit(new_ids);
+        })
+    };
+    // keeps the columns lined up with the rows' expand toggle
+    let detail_spacer = props.render_detail.is_some().then(|| html! {
+        <div class={prefix.class("yew-data-grid-detail-toggle-cell")}></div>
+    });
+    let history = use_mut_ref(EditHistory::<T::IdType, U>::default);
+    let active_cell = use_state(|| None::<(T::IdType, U)>);
+    // the rows that pasting and exporting read when they run, replaced further down on every render
+    let export_view = use_mut_ref(|| None::<ExportView<T::IdType, T, U>>);
+
+    // drop state that refers to rows which no longer exist
+    if let Some(removed) = row_diff.as_ref().map(|diff| &diff.removed).filter(|removed| !removed.is_empty()) {
+        if selection.iter().any(|id| removed.contains(id)) {
+            let selected: Vec<T::IdType> = selection.iter().filter(|id| !removed.contains(id)).cloned().collect();
+            props.on_selection_change.emit(selected.clone());
+            selection.set(selected);
+        }
+        if editing.as_ref().is_some_and(|e| removed.contains(&e.row_id)) {
+            *edit_session.borrow_mut() += 1;
+            editing.set(None);
+        }
+        if row_editing.borrow().as_ref().is_some_and(|e| removed.contains(&e.row_id)) {
+            *edit_session.borrow_mut() += 1;
+            row_editing.replace(None);
+        }
+        if active_cell.as_ref().is_some_and(|(id, _)| removed.contains(id)) {
+            active_cell.set(None);
+        }
+        measured_heights.borrow_mut().retain(|id, _| !removed.contains(id));
+        if expanded_state.iter().any(|id| removed.contains(id)) {
+            expanded_state.set(expanded_state.iter().filter(|id| !removed.contains(id)).cloned().collect());
+        }
+    }
+    let on_cell_edit_commit = {
+        let history = history.clone();
+        let on_cell_edit_commit = props.on_cell_edit_commit.clone();
+        Callback::from(move |edit: CellEdit<T::IdType, U>| {
+            history.borrow_mut().record(vec![edit.clone()]);
+            on_cell_edit_commit.emit(edit);
+        })
+    };
+    let on_row_edit_commit = {
+        let history = history.clone();
+        let on_row_edit_commit = props.on_row_edit_commit.clone();
+        Callback::from(move |edit: RowEdit<T::IdType, U>| {
+            history.borrow_mut().record_row(edit.clone());
+            on_row_edit_commit.emit(edit);
+        })
+    };
+    // pastes, and undo and redo of cell edits, change several cells at once
+    let apply_edits = {
+        let on_edit_batch_commit = props.on_edit_batch_commit.clone();
+        let on_cell_edit_commit = props.on_cell_edit_commit.clone();
+        Callback::from(move |edits: Vec<CellEdit<T::IdType, U>>| {
+            match &on_edit_batch_commit {
+                Some(on_edit_batch_commit) => on_edit_batch_commit.emit(edits),
+                None => edits.into_iter().for_each(|edit| on_cell_edit_commit.emit(edit))
+            }
+        })
+    };
+    let undo = {
+        let history = history.clone();
+        let apply_edits = apply_edits.clone();
+        let on_row_edit_commit = props.on_row_edit_commit.clone();
+        Callback::from(move |_: ()| {
+            let transaction = history.borrow_mut().undo();
+            match transaction {
+                Some(EditTransaction::Cells(edits)) => apply_edits.emit(edits),
+                Some(EditTransaction::Row(edit)) => on_row_edit_commit.emit(edit),
+                None => {}
+            }
+        })
+    };
+    let redo = {
+        let history = history.clone();
+        let apply_edits = apply_edits.clone();
+        let on_row_edit_commit = props.on_row_edit_commit.clone();
+        Callback::from(move |_: ()| {
+            let transaction = history.borrow_mut().redo();
+            match transaction {
+                Some(EditTransaction::Cells(edits)) => apply_edits.emit(edits),
+                Some(EditTransaction::Row(edit)) => on_row_edit_commit.emit(edit),
+                None => {}
+            }
+        })
+    };
+    let onkeydown = {
+        let undo = undo.clone();
+        let redo = redo.clone();
+        Callback::from(move |e: KeyboardEvent| {
+        // inputs keep their own undo
+        if is_input_target(&e) || !(e.ctrl_key() || e.meta_key()) {
+            return;
+        }
+        match e.key().to_lowercase().as_str() {
+            "z" if e.shift_key() => redo.emit(()),
+            "z" => undo.emit(()),
+            "y" => redo.emit(()),
+            _ => return
+        }
+        e.prevent_default();
+        })
+    };
+    let onpaste = {
+        let active_cell = active_cell.clone();
+        let history = history.clone();
+        let apply_edits = apply_edits.clone();
+        let export_view = export_view.clone();
+        let visible_columns = visible_columns.clone();
+        Callback::from(move |e: Event| {
+            let (anchor_id, anchor_column) = match active_cell.as_ref() {
+                Some(anchor) if !is_input_target(&e) => anchor.clone(),
+                _ => return
+            };
+            let text = e.dyn_ref::<ClipboardEvent>()
+                .and_then(|e| e.clipboard_data())
+                .and_then(|data| data.get_data("text/plain").ok())
+                .unwrap_or_default();
+            let values = parse_clipboard_text(&text);
+            // pasting fills the rows of the current page
+            let edits = match export_view.borrow().as_ref() {
+                Some(view) => paste_edits(&view.page_rows(), &visible_columns, (&anchor_id, anchor_column), &values),
+                None => return
+            };
+            if !edits.is_empty() {
+                e.prevent_default();
+                history.borrow_mut().record(edits.clone());
+                apply_edits.emit(edits);
+            }
+        })
+    };
+    let cancel_row_edit = {
+        let row_editing = row_editing.clone();
+        let edit_session = edit_session.clone();
+        let force_update = force_update.clone();
+        Callback::from(move |_: ()| {
+            *edit_session.borrow_mut() += 1;
+            *row_editing.borrow_mut() = None;
+            force_update.force_update();
+        })
+    };
+
     let grid = {
-        // let page_view = &row_state.borrow().sort_order;
-        let page_view = use_page_view(*pg, &row_state.borrow().sort_order);
-        page_view.iter().map(|i| {
-            let row_key = i.to_string();
-            let row = &props.rows[row_state.borrow().row_index_map[&row_key]];
-            const CELL_HEIGHT: i32 = 52;
-            let cell_values = props.columns.iter().enumerate().map(|(i,col)| {
-                let value = col.get_value(row);
+        let page_offset = ((pagination.page - 1) * pagination.page_size) as usize;
+        page_view.iter().enumerate().map(|(view_index, grouped_row)| {
+            let row_index = page_offset + view_index;
+            let row_key = match grouped_row {
+                GroupedRow::Row(id) => id,
+                GroupedRow::Group { key, depth, rows, expanded } => {
+                    let onclick = {
+                        let group_expansion = group_expansion.clone();
+                        let key = key.clone();
+                        Callback::from(move |_| {
+                            let mut expansion = (*group_expansion).clone();
+                            expansion.toggle(key.clone());
+                            group_expansion.set(expansion);
+                        })
+                    };
+                    let header_name = group_by[*depth].get_config().header_name;
+                    let value = key.last().cloned().unwrap_or_default();
+                    let toggle = if *expanded { "\u{25bc}" } else { "\u{25b6}" };
+                    let state = row_state.borrow();
+                    // the label goes in the first column, every column shows its aggregate for the group
+                    let cells = visible_columns.iter().enumerate().map(|(i, col)| {
+                        let aggregate = aggregate_column(rows, &props.rows, &state.row_index_map, col);
+                        let label = (i == 0).then(|| html! {
+                            <>
+                                <span class={prefix.class("yew-data-grid-group-toggle")}>{toggle}</span>
+                                {format!("{header_name}: {value}")}
+                                <span class={prefix.class("yew-data-grid-group-count")}>{format!("({})", rows.len())}</span>
+                            </>
+                        });
+                        let indent = if i == 0 { *depth } else { 0 };
+                        let style = format!("width: {}px; min-height: {base_row_height}px; \
+                                             padding-left: calc({indent} * var(--yew-data-grid-indent) + 8px)",
+                                            column_state.width(col, col.get_config().width));
+                        html! {
+                            <div class={prefix.class("yew-data-grid-group-cell")} {style}>
+                                {label}
+                                <span class={prefix.class("yew-data-grid-group-aggregate")}>{aggregate}</span>
+                            </div>
+                        }
+                    }).collect::<Html>();
+                    return html! {
+                        <div class={classes!(prefix.class("yew-data-grid-row"), prefix.class("yew-data-grid-group-row"))} key={row_dom_key(key)}
+                            row-index={row_index.to_string()} {onclick}>
+                            {detail_spacer.clone()}
+                            {cells}
+                        </div>
+                    };
+                }
+            };
+            let row = &props.rows[row_state.borrow().row_index_map[row_key]];
+            let selected = selection.contains(row_key);
+            let row_edit = row_editing.borrow().as_ref().filter(|e| e.row_id == *row_key).cloned();
+            let row_height = row_height_of(row);
+            // editors may need more room than a fixed height row has
+            let fixed_height = !props.auto_row_height && row_edit.is_none()
+                && !editing.as_ref().is_some_and(|e| e.row_id == *row_key);
+            let height_style = if fixed_height { format!("height: {row_height}px") } else { format!("min-height: {row_height}px") };
+            let cell_values = visible_columns.iter().enumerate().map(|(i,col)| {
+                let config = col.get_config();
+                let cell_edit = editing.as_ref().filter(|e| config.editable && e.row_id == *row_key && e.column == *col);
+                let row_cell_edit = row_edit.as_ref().filter(|_| config.editable);
+                let cell_editing = cell_edit.is_some() || row_cell_edit.is_some();
+                let ctx = CellContext {
+                    row_id: row_key.clone(),
+                    row_index,
+                    col_index: i,
+                    selected,
+                    editing: cell_editing
+                };
+                let value = if let Some(row_edit) = row_cell_edit {
+                    let original = col.get_value(row);
+                    let value = row_edit.draft(col).map(str::to_string).unwrap_or_else(|| original.clone());
+                    let on_commit = {
+                        let row_editing = row_editing.clone();
+                        let force_update = force_update.clone();
+                        let row_id = row_key.clone();
+                        let column = *col;
+                        Callback::from(move |value: String| {
+                            // blur fires after the row was saved or cancelled, the draft is gone by then
+                            if let Some(row_edit) = row_editing.borrow_mut().as_mut().filter(|e| e.row_id == row_id) {
+                                row_edit.set_value(column, &original, value);
+                            }
+                            force_update.force_update();
+                        })
+                    };
+                    let error = row_edit.error(col).map(|e| AttrValue::from(e.to_string()));
+                    html! {
+                        <GridCellEditor editor={config.editor.clone()} {value} {error} pending={row_edit.pending}
+                            autofocus={row_edit.focus == *col} keep_open=true {on_commit} on_cancel={cancel_row_edit.clone()}/>
+                    }
+                } else if let Some(cell_edit) = cell_edit {
+                    let old_value = col.get_value(row);
+                    let on_commit = {
+                        let editing = editing.clone();
+                        let edit_session = edit_session.clone();
+                        let on_cell_edit_commit = on_cell_edit_commit.clone();
+                        let row = row.clone();
+                        let row_id = row_key.clone();
+                        let column = *col;
+                        let old_value = old_value.clone();
+                        Callback::from(move |new_value: String| {
+                            if new_value == old_value {
+                                editing.set(None);
+                                return;
+                            }
+                            let edit = CellEdit { row_id: row_id.clone(), column, old_value: old_value.clone(), new_value };
+                            commit_cell_edit(&row, edit, editing.clone(), edit_session.clone(), on_cell_edit_commit.clone());
+                        })
+                    };
+                    let on_cancel = {
+                        let editing = editing.clone();
+                        let edit_session = edit_session.clone();
+                        Callback::from(move |_| {
+                            *edit_session.borrow_mut() += 1;
+                            editing.set(None);
+                        })
+                    };
+                    let value = cell_edit.draft.clone().unwrap_or(old_value);
+                    let error = cell_edit.error.clone().map(AttrValue::from);
+                    html! {
+                        <GridCellEditor editor={config.editor.clone()} {value} {error} pending={cell_edit.pending}
+                            {on_commit} {on_cancel}/>
+                    }
+                } else {
+                    col.render_cell(row, &ctx)
+                };
+                let tree_column = props.tree_data.as_ref().is_some_and(|tree| tree.column == *col);
+                let tree_node = tree_nodes.get(row_key).filter(|_| tree_column && !cell_editing);
+                let value = match tree_node {
+                    Some(node) => {
+                        let toggle = node.expandable.then(|| {
+                            let onclick = {
+                                let toggle_tree_row = toggle_tree_row.clone();
+                                let id = row_key.clone();
+                                let node = *node;
+                                Callback::from(move |e: MouseEvent| {
+                                    e.stop_propagation();
+                                    toggle_tree_row.emit((id.clone(), node));
+                                })
+                            };
+                            let icon = if node.loading() { "\u{2026}" } else if node.expanded { "\u{25bc}" } else { "\u{25b6}" };
+                            html! { <button class={prefix.class("yew-data-grid-tree-toggle")} {onclick}>{icon}</button> }
+                        });
+                        let style = format!("padding-left: calc({} * var(--yew-data-grid-indent))", node.depth);
+                        html! {
+                            <div class={prefix.class("yew-data-grid-tree-cell")} {style}>
+                                <span class={prefix.class("yew-data-grid-tree-toggle-container")}>{toggle}</span>
+                                {value}
+                            </div>
+                        }
+                    }
+                    None => value
+                };
+                let ondblclick = config.editable.then(|| {
+                    let row_id = row_key.clone();
+                    let column = *col;
+                    start_editing.reform(move |_: MouseEvent| (row_id.clone(), column))
+                });
+                let onclick = {
+                    let active_cell = active_cell.clone();
+                    let row_id = row_key.clone();
+                    let column = *col;
+                    Callback::from(move |_: MouseEvent| active_cell.set(Some((row_id.clone(), column))))
+                };
+                let cell_active = active_cell.as_ref().is_some_and(|(id, c)| id == row_key && c == col);
                 let col_index_str = i.to_string();
-                let cell_width = col.get_config().width;
-                let style = format!("width: {cell_width}px; min-height: {CELL_HEIGHT}px;");
+                let cell_width = column_state.width(col, config.width);
+                let rule = cell_rule(&props.style_rules, row, col);
+                let mut style = format!("width: {cell_width}px; {height_style};");
+                for extra in [col.get_cell_style(row).as_ref(), rule.and_then(|rule| rule.style.as_ref())].into_iter().flatten() {
+                    style.push(' ');
+                    style.push_str(extra);
+                }
+                let cell_invalid = cell_edit.is_some_and(|e| e.error.is_some()) || row_cell_edit.is_some_and(|e| e.error(col).is_some());
+                let cell_class = classes!(prefix.class("yew-data-grid-cell"),
+                    column_state.is_pinned(col).then(|| prefix.class("yew-data-grid-cell-pinned")),
+                    cell_editing.then(|| prefix.class("yew-data-grid-cell-editing")),
+                    row_cell_edit.is_some_and(|e| e.is_dirty(col)).then(|| prefix.class("yew-data-grid-cell-dirty")),
+                    cell_invalid.then(|| prefix.class("yew-data-grid-cell-invalid")),
+                    cell_active.then(|| prefix.class("yew-data-grid-cell-active")),
+                    col.get_cell_class(row),
+                    rule.and_then(|rule| rule.class.clone()));
                 html! {
-                <div class="yew-data-grid-cell" style={style} row-index={row_key.clone()} col-index={col_index_str}>
-                    <div class="yew-data-grid-cell-content">{value}</div>
+                <div class={cell_class} style={style} row-index={row_index.to_string()} col-index={col_index_str} {onclick} {ondblclick}>
+                    <div class={prefix.class("yew-data-grid-cell-content")}>{value}</div>
                 </div>
             }
             }).collect::<Html>();
-            let key = row.get_id();
-            let style = format!("width: 100%; min-height: {CELL_HEIGHT}px; display: flex");
+            let key = row_dom_key(row_key);
+            let style = format!("width: 100%; {height_style}; display: flex");
+            let row_edit_actions = row_edit.as_ref().map(|row_edit| {
+                let save = {
+                    let row_editing = row_editing.clone();
+                    let edit_session = edit_session.clone();
+                    let force_update = force_update.clone();
+                    let on_row_edit_commit = on_row_edit_commit.clone();
+                    let row = row.clone();
+                    Callback::from(move |_| save_row_edit(&row, row_editing.clone(), edit_session.clone(),
+                                                          force_update.clone(), on_row_edit_commit.clone()))
+                };
+                html! {
+                    <div class={prefix.class("yew-data-grid-row-edit-actions")}>
+                        <button class={prefix.class("yew-data-grid-row-edit-button")} onclick={save} disabled={row_edit.pending}>{"Save"}</button>
+                        <button class={prefix.class("yew-data-grid-row-edit-button")} onclick={cancel_row_edit.reform(|_| ())}>{"Cancel"}</button>
+                    </div>
+                }
+            });
             let empty_cell = html! {
-                <div class="yew-data-grid-cell" style={style} row-index={row_key.clone()} col-index="0">
-                    <div class="yew-data-grid-cell-content"></div>
+                <div class={prefix.class("yew-data-grid-cell")} style={style} row-index={row_index.to_string()} col-index={visible_columns.len().to_string()}>
+                    <div class={prefix.class("yew-data-grid-cell-content")}>{row_edit_actions}</div>
+                </div>
+            };
+            let detail_expanded = expanded_ids.contains(row_key);
+            let detail_toggle = props.render_detail.as_ref().map(|_| {
+                let onclick = {
+                    let toggle_detail = toggle_detail.clone();
+                    let id = row_key.clone();
+                    Callback::from(move |e: MouseEvent| {
+                        e.stop_propagation();
+                        toggle_detail.emit(id.clone());
+                    })
+                };
+                let icon = if detail_expanded { "\u{25bc}" } else { "\u{25b6}" };
+                html! {
+                    <div class={prefix.class("yew-data-grid-detail-toggle-cell")}>
+                        <button class={prefix.class("yew-data-grid-detail-toggle")} {onclick}>{icon}</button>
+                    </div>
+                }
+            });
+            let detail_panel = props.render_detail.as_ref().filter(|_| detail_expanded).map(|render_detail| html! {
+                <div class={prefix.class("yew-data-grid-detail-panel")} row-index={row_index.to_string()} onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                    {render_detail.call(row)}
                 </div>
+            });
+            let rule = row_rule(&props.style_rules, row);
+            let mut row_style = format!("width: 100%; {height_style};");
+            if let Some(style) = rule.and_then(|rule| rule.style.as_ref()) {
+                row_style.push(' ');
+                row_style.push_str(style);
+            }
+            let row_class = classes!(prefix.class("yew-data-grid-row"),
+                selected.then(|| prefix.class("yew-data-grid-row-selected")),
+                row_edit.is_some().then(|| prefix.class("yew-data-grid-row-editing")),
+                detail_expanded.then(|| prefix.class("yew-data-grid-row-expanded")),
+                fixed_height.then(|| prefix.class("yew-data-grid-row-fixed-height")),
+                (row_index % 2 == 1).then(|| prefix.class("yew-data-grid-row-odd")),
+                props.get_row_class.as_ref().map(|get_row_class| get_row_class.call(row)),
+                rule.and_then(|rule| rule.class.clone()));
+            let onclick = {
+                let on_row_click = on_row_click.clone();
+                let row_key = row_key.clone();
+                Callback::from(move |e: MouseEvent| on_row_click.emit((row_key.clone(), e)))
             };
-            let row_style = format!("width: 100%; min-height: {CELL_HEIGHT}px;");
             html! (
-            <div class="yew-data-grid-row" key={key.to_string()} style={row_style} row-index={row_key}>
-                {cell_values}
-                {empty_cell}
-            </div>
+            // the key is on the fragment so the detail panel moves with its row
+            <key={key}>
+                <div class={row_class} style={row_style} row-index={row_index.to_string()} {onclick}>
+                    {detail_toggle}
+                    {cell_values}
+                    {empty_cell}
+                </div>
+                {detail_panel}
+            </>
         )
         }).collect::<Html>()
     };
-    let table_style = format!("width: 100%; min-height: 52px;");
+    let scrollable = use_node_ref();
+    let pending_scroll = use_mut_ref(|| None::<ScrollTarget>);
+    {
+        // rows and columns only exist in the DOM once the page they are on has rendered
+        let scrollable = scrollable.clone();
+        let pending_scroll = pending_scroll.clone();
+        let prefix = prefix.clone();
+        use_effect(move || {
+            if let (Some(target), Some(container)) = (pending_scroll.take(), scrollable.cast::<Element>()) {
+                let selector = match target {
+                    ScrollTarget::Row(row_index) => format!(".{}[row-index=\"{row_index}\"]", prefix.class("yew-data-grid-row")),
+                    ScrollTarget::Column(col_index) => format!(".{}[col-index=\"{col_index}\"]", prefix.class("yew-data-grid-cell"))
+                };
+                if let Ok(Some(element)) = container.query_selector(&selector) {
+                    element.scroll_into_view();
+                }
+            }
+            || {}
+        });
+    }
+    {
+        let scrollable = scrollable.clone();
+        let measured_heights = measured_heights.clone();
+        let visible_rows = visible_rows.clone();
+        let auto_row_height = props.auto_row_height;
+        let prefix = prefix.clone();
+        use_effect(move || {
+            if let (true, Some(container)) = (auto_row_height, scrollable.cast::<Element>()) {
+                measure_row_heights(&container, &prefix, &visible_rows, &mut measured_heights.borrow_mut());
+            }
+            || {}
+        });
+    }
+    {
+        let scrollable = scrollable.clone();
+        let detail_heights = detail_heights.clone();
+        let visible_rows = visible_rows.clone();
+        let has_details = props.render_detail.is_some();
+        let prefix = prefix.clone();
+        use_effect(move || {
+            if let (true, Some(container)) = (has_details, scrollable.cast::<Element>()) {
+                measure_detail_heights(&container, &prefix, &visible_rows, &mut detail_heights.borrow_mut());
+            }
+            || {}
+        });
+    }
+    {
+        // the print dialog opens once every row is in the DOM, the grid goes back to its pages after it closes
+        let print_requested = print_requested.clone();
+        use_effect(move || {
+            if *print_requested {
+                if let Some(Err(e)) = web_sys::window().map(|window| window.print()) {
+                    log::error!("print failed: {e:?}");
+                }
+                print_requested.set(false);
+            }
+            || {}
+        });
+    }
+    let import_open = use_state(|| false);
+    export_view.replace(Some(ExportView {
+        rows: props.rows.clone(),
+        row_state: row_state.clone(),
+        view_ids: view_ids.clone(),
+        page_ids: page_ids.clone(),
+        selected: (*selection).clone(),
+        columns: visible_columns.clone()
+    }));
+    let export_buttons = {
+        let csv_button = props.csv_export.clone().map(|options| {
+            let view = export_view.clone();
+            let onclick = Callback::from(move |_| {
+                let Some(csv) = view.borrow().as_ref().map(|view| view.to_csv(&options)) else { return };
+                if let Err(e) = download(&options.file_name, "text/csv;charset=utf-8", csv.as_bytes()) {
+                    log::error!("csv download failed: {e:?}");
+                }
+            });
+            html! { <button class={prefix.class("yew-data-grid-toolbar-button")} {onclick}>{"Export CSV"}</button> }
+        });
+        let json_button = props.json_export.clone().map(|options| {
+            let view = export_view.clone();
+            let onclick = Callback::from(move |_| {
+                let Some(json) = view.borrow().as_ref().map(|view| view.to_json(&options)) else { return };
+                if let Err(e) = download(&options.file_name, options.format.mime_type(), json.as_bytes()) {
+                    log::error!("json download failed: {e:?}");
+                }
+            });
+            html! { <button class={prefix.class("yew-data-grid-toolbar-button")} {onclick}>{"Export JSON"}</button> }
+        });
+        html! { <>{csv_button}{json_button}</> }
+    };
+    let import_button = props.csv_import.is_some().then(|| {
+        let import_open = import_open.clone();
+        let onclick = Callback::from(move |_| import_open.set(true));
+        html! { <button class={prefix.class("yew-data-grid-toolbar-button")} {onclick}>{"Import CSV"}</button> }
+    });
+    let toolbar = (!print_mode && (props.csv_export.is_some() || props.json_export.is_some() || import_button.is_some())).then(|| html! {
+        <div class={prefix.class("yew-data-grid-toolbar")}>
+            {import_button}
+            {export_buttons}
+        </div>
+    });
+    let import_dialog = props.csv_import.clone().filter(|_| *import_open && !print_mode).map(|options| {
+        let on_close = {
+            let import_open = import_open.clone();
+            Callback::from(move |_| import_open.set(false))
+        };
+        html! {
+            <GridCsvImport<U> columns={props.columns.clone()} {options} on_import={props.on_import.clone()} {on_close}/>
+        }
+    });
+    if let Some(api) = &props.api {
+        // the api pages like the pagination bar, also while the grid is printing
+        let paged = Pagination::new(visible_rows.len(), props.page_size);
+        let go_to_row = {
+            let visible_rows = visible_rows.clone();
+            let on_page_change = on_page_change.clone();
+            move |id: &T::IdType| -> Option<usize> {
+                let index = visible_rows.iter().position(|row| matches!(row, GroupedRow::Row(row_id) if row_id == id))?;
+                on_page_change.emit(paged.page_of(index));
+                Some(index)
+            }
+        };
+        let scroll_to_row = {
+            let go_to_row = go_to_row.clone();
+            let pending_scroll = pending_scroll.clone();
+            let force_update = force_update.clone();
+            Callback::from(move |id: T::IdType| {
+                if let Some(row_index) = go_to_row(&id) {
+                    pending_scroll.replace(Some(ScrollTarget::Row(row_index)));
+                    force_update.force_update();
+                }
+            })
+        };
+        let scroll_to_column = {
+            let visible_columns = visible_columns.clone();
+            let pending_scroll = pending_scroll.clone();
+            let force_update = force_update.clone();
+            Callback::from(move |column: U| {
+                if let Some(col_index) = visible_columns.iter().position(|c| *c == column) {
+                    pending_scroll.replace(Some(ScrollTarget::Column(col_index)));
+                    force_update.force_update();
+                }
+            })
+        };
+        let select_rows = {
+            let selection = selection.clone();
+            let on_selection_change = props.on_selection_change.clone();
+            Callback::from(move |ids: Vec<T::IdType>| {
+                on_selection_change.emit(ids.clone());
+                selection.set(ids);
+            })
+        };
+        let start_editing = {
+            let start_editing = start_editing.clone();
+            let visible_rows = visible_rows.clone();
+            let visible_columns = visible_columns.clone();
+            Callback::from(move |(id, column): (T::IdType, U)| {
+                check_editable(&visible_rows, &visible_columns, &id, &column)?;
+                go_to_row(&id);
+                start_editing.emit((id, column));
+                Ok(())
+            })
+        };
+        let get_sorted_ids = {
+            let sorted_ids = sorted_ids.clone();
+            Callback::from(move |_| (*sorted_ids).clone())
+        };
+        let get_filtered_ids = {
+            let view_ids = view_ids.clone();
+            Callback::from(move |_| (*view_ids).clone())
+        };
+        let row_offset = {
+            let page_view = page_view.clone();
+            let measured_heights = measured_heights.clone();
+            let detail_heights = detail_heights.clone();
+            let state = row_state.borrow();
+            let configured_heights: Vec<i32> = page_view.iter().map(|entry| match entry {
+                GroupedRow::Row(id) => row_height_of(&props.rows[state.row_index_map[id]]),
+                GroupedRow::Group { .. } => base_row_height
+            }).collect();
+            Callback::from(move |id: T::IdType| {
+                let index = page_view.iter().position(|entry| matches!(entry, GroupedRow::Row(row_id) if *row_id == id))?;
+                page_heights(&page_view, &configured_heights, &measured_heights.borrow(), &detail_heights.borrow()).offset(index)
+            })
+        };
+        let refresh = {
+            let refresh_requested = refresh_requested.clone();
+            let force_update = force_update.clone();
+            Callback::from(move |_| {
+                refresh_requested.replace(true);
+                force_update.force_update();
+            })
+        };
+        let export_csv = {
+            let view = export_view.clone();
+            Callback::from(move |options: CsvOptions| view.borrow().as_ref().map(|view| view.to_csv(&options)).unwrap_or_default())
+        };
+        let export_json = {
+            let view = export_view.clone();
+            Callback::from(move |options: JsonOptions| view.borrow().as_ref().map(|view| view.to_json(&options)).unwrap_or_default())
+        };
+        #[cfg(feature = "xlsx")]
+        let export_xlsx = {
+            let view = export_view.clone();
+            Callback::from(move |options: crate::xlsx::XlsxOptions| {
+                view.borrow().as_ref().map_or(Err(GridError::GridNotRendered), |view| view.to_xlsx(&options))
+            })
+        };
+        let go_to_page = {
+            let on_page_change = on_page_change.clone();
+            Callback::from(move |page: i32| on_page_change.emit(paged.clamp_page(page)))
+        };
+        let print = {
+            let print_requested = print_requested.clone();
+            Callback::from(move |_| print_requested.set(true))
+        };
+        let apply_transaction = {
+            let transactions = transactions.clone();
+            Callback::from(move |diff: RowDiff<T::IdType>| transactions.borrow_mut().push(diff))
+        };
+        api.register(GridApiHandlers {
+            undo,
+            redo,
+            scroll_to_row,
+            scroll_to_column,
+            go_to_page,
+            select_rows,
+            start_editing,
+            get_sorted_ids,
+            get_filtered_ids,
+            export_csv,
+            export_json,
+            #[cfg(feature = "xlsx")]
+            export_xlsx,
+            row_offset,
+            print,
+            refresh,
+            apply_transaction
+        });
+    }
+
+    // totals over the filtered rows, only shown when a visible column declares an aggregation
+    let aggregate_row = visible_columns.iter().any(|col| col.get_config().aggregation.is_some()).then(|| {
+        let state = row_state.borrow();
+        let cells = visible_columns.iter().map(|col| {
+            let config = col.get_config();
+            let style = format!("width: {}px", column_state.width(col, config.width));
+            html! {
+                <div class={prefix.class("yew-data-grid-aggregate-cell")} {style}>
+                    {aggregate_column(&view_ids, &props.rows, &state.row_index_map, col)}
+                </div>
+            }
+        }).collect::<Html>();
+        html! {
+            <div class={prefix.class("yew-data-grid-aggregate-row")}>
+                {detail_spacer.clone()}
+                {cells}
+            </div>
+        }
+    });
+
+    let table_style = format!("width: 100%; min-height: {base_row_height}px;");
+    // fills the space after the last visible column, same as the empty cell at the end of each row
     let empty_header = html! {
-        <div class="yew-data-grid-header-cell" style="width: 100%; display: flex"></div>
+        <div class={prefix.class("yew-data-grid-header-cell")} style="width: 100%; display: flex"></div>
     };
 
+    let header_row = html! {
+        <div class={prefix.class("yew-data-grid-header-row")} style={table_style}>
+            {detail_spacer}
+            {columns}
+            {empty_header}
+        </div>
+    };
+    // print css lays the grid out as a table so the header group repeats on every printed page
+    let header_row = if print_mode {
+        html! { <div class={prefix.class("yew-data-grid-print-header")}>{header_row}</div> }
+    } else {
+        header_row
+    };
+    let pagination_bar = (!print_mode).then(|| html! {
+        <div class={prefix.class("yew-data-grid-footer-container")}>
+           <GridPaginationBar pagination={pagination} on_page_change={on_page_change} max_pages_to_show=10/>
+        </div>
+    });
+    let container_class = classes!(prefix.class("yew-data-grid-container"), print_mode.then(|| prefix.class("yew-data-grid-print")));
+    let container_style = props.theme.as_ref().map(Theme::css_variables);
+
     html!(
-         <div class="yew-data-grid-container">
-            <style>{DATA_GRID_STYLE}</style>
-            <div class="yew-data-grid-header-row" style={table_style}>
-                {columns}
-                {empty_header}
-            </div>
-            <div class="yew-data-grid-scrollable">
-                {grid}
-            </div>
-            <div class="yew-data-grid-footer-container">
-               <GridPaginationBar pagination={pg} max_pages_to_show=10/>
+        <ContextProvider<ClassPrefix> context={prefix.clone()}>
+            <div class={container_class} style={container_style} tabindex="0" {onkeydown} {onpaste}>
+                {toolbar}
+                {import_dialog}
+                {header_row}
+                <div class={prefix.class("yew-data-grid-scrollable")} ref={scrollable}>
+                    {error_overlay}
+                    {grid}
+                </div>
+                {aggregate_row}
+                {pagination_bar}
             </div>
-        </div>
+        </ContextProvider<ClassPrefix>>
     )
 }
 
+enum ScrollTarget {
+    /// Position of the row in the filtered view, as in its `row-index` attribute
+    Row(usize),
+    Column(usize)
+}
+
+/// Yew key of a row, hashed so ids of any type can key the row elements
+fn row_dom_key<I: Hash>(id: &I) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Records the rendered height of every data row in the container, keyed by row id
+fn measure_row_heights<I: Hash + Eq + Clone>(container: &Element,
+                                             prefix: &ClassPrefix,
+                                             visible_rows: &[GroupedRow<I>],
+                                             measured_heights: &mut HashMap<I, i32>) {
+    let elements = match container.query_selector_all(&format!(".{}[row-index]", prefix.class("yew-data-grid-row"))) {
+        Ok(elements) => elements,
+        Err(_) => return
+    };
+    for i in 0..elements.length() {
+        let element = match elements.item(i).and_then(|node| node.dyn_into::<HtmlElement>().ok()) {
+            Some(element) => element,
+            None => continue
+        };
+        let row_index = element.get_attribute("row-index").and_then(|index| index.parse::<usize>().ok());
+        if let Some(GroupedRow::Row(id)) = row_index.and_then(|index| visible_rows.get(index)) {
+            measured_heights.insert(id.clone(), element.offset_height());
+        }
+    }
+}
+
+/// Records the rendered height of every expanded detail panel in the container, keyed by the id of its row
+fn measure_detail_heights<I: Hash + Eq + Clone>(container: &Element,
+                                                prefix: &ClassPrefix,
+                                                visible_rows: &[GroupedRow<I>],
+                                                detail_heights: &mut HashMap<I, i32>) {
+    let elements = match container.query_selector_all(&format!(".{}[row-index]", prefix.class("yew-data-grid-detail-panel"))) {
+        Ok(elements) => elements,
+        Err(_) => return
+    };
+    // collapsed panels are no longer in the DOM and take no room
+    detail_heights.clear();
+    for i in 0..elements.length() {
+        let element = match elements.item(i).and_then(|node| node.dyn_into::<HtmlElement>().ok()) {
+            Some(element) => element,
+            None => continue
+        };
+        let row_index = element.get_attribute("row-index").and_then(|index| index.parse::<usize>().ok());
+        if let Some(GroupedRow::Row(id)) = row_index.and_then(|index| visible_rows.get(index)) {
+            detail_heights.insert(id.clone(), element.offset_height());
+        }
+    }
+}
+
+/// Room each entry of the page takes up: the measured height of a row, or its configured height until
+/// it is measured, plus the height of its expanded detail panel
+fn page_heights<I: Hash + Eq>(page_view: &[GroupedRow<I>],
+                              configured_heights: &[i32],
+                              measured_heights: &HashMap<I, i32>,
+                              detail_heights: &HashMap<I, i32>) -> RowHeights {
+    RowHeights::new(page_view.iter().zip(configured_heights).map(|(entry, height)| match entry {
+        GroupedRow::Row(id) => measured_heights.get(id).copied().unwrap_or(*height) + detail_heights.get(id).copied().unwrap_or(0),
+        GroupedRow::Group { .. } => *height
+    }))
+}
+
+fn is_input_target(e: &Event) -> bool {
+    e.target_dyn_into::<Element>()
+        .map(|target| matches!(target.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
+        .unwrap_or(false)
+}
+
+/// Cell currently being edited, an invalid value is kept as the draft until it is fixed or cancelled
+#[derive(Clone, Debug, PartialEq)]
+struct EditingCell<I, U> {
+    row_id: I,
+    column: U,
+    draft: Option<String>,
+    error: Option<String>,
+    pending: bool
+}
+
+impl<I: Clone, U: Copy> EditingCell<I, U> {
+    fn new(row_id: I, column: U) -> Self {
+        Self { row_id, column, draft: None, error: None, pending: false }
+    }
+
+    fn from_edit(edit: &CellEdit<I, U>, error: Option<String>, pending: bool) -> Self {
+        Self { row_id: edit.row_id.clone(), column: edit.column, draft: Some(edit.new_value.clone()), error, pending }
+    }
+}
+
+/// Whether the api can open an editor on the cell, the row must be shown and the column visible and editable
+fn check_editable<I: Debug + PartialEq, U: GridDataColumn + PartialEq>(visible_rows: &[GroupedRow<I>],
+                                                                      visible_columns: &[U],
+                                                                      id: &I,
+                                                                      column: &U) -> Result<(), GridError> {
+    if !visible_rows.iter().any(|row| matches!(row, GroupedRow::Row(row_id) if row_id == id)) {
+        return Err(GridError::RowNotFound(format!("{id:?}")));
+    }
+    let config = column.get_config();
+    if !config.editable || !visible_columns.contains(column) {
+        return Err(GridError::ColumnNotEditable(config.header_name));
+    }
+    Ok(())
+}
+
+/// Runs the column's validation before emitting the edit, invalid cells stay in edit mode
+fn commit_cell_edit<I, T, U>(row: &T,
+                             edit: CellEdit<I, U>,
+                             editing: UseStateHandle<Option<EditingCell<I, U>>>,
+                             edit_session: Rc<RefCell<u32>>,
+                             on_cell_edit_commit: Callback<CellEdit<I, U>>)
+    where I: Clone + 'static, U: GridDataColumn<RowType=T> + Copy + 'static
+{
+    if let Err(error) = edit.column.validate(row, &edit.new_value) {
+        editing.set(Some(EditingCell::from_edit(&edit, Some(error), false)));
+        return;
+    }
+    let validation = match edit.column.validate_async(row, &edit.new_value) {
+        Some(validation) => validation,
+        None => {
+            on_cell_edit_commit.emit(edit);
+            editing.set(None);
+            return;
+        }
+    };
+    editing.set(Some(EditingCell::from_edit(&edit, None, true)));
+    let session = *edit_session.borrow();
+    spawn_local(async move {
+        let result = validation.await;
+        if *edit_session.borrow() != session {
+            return;
+        }
+        match result {
+            Ok(()) => {
+                on_cell_edit_commit.emit(edit);
+                editing.set(None);
+            }
+            Err(error) => editing.set(Some(EditingCell::from_edit(&edit, Some(error), false)))
+        }
+    });
+}
+
+/// Validates every changed cell of the row and emits the changes once all of them pass
+fn save_row_edit<I, T, U>(row: &T,
+                          row_editing: Rc<RefCell<Option<RowEditing<I, U>>>>,
+                          edit_session: Rc<RefCell<u32>>,
+                          force_update: UseForceUpdateHandle,
+                          on_row_edit_commit: Callback<RowEdit<I, U>>)
+    where I: Clone + 'static, U: GridDataColumn<RowType=T> + Copy + PartialEq + 'static
+{
+    let (row_id, changes) = match row_editing.borrow().as_ref() {
+        Some(row_edit) => (row_edit.row_id.clone(), row_edit.changes(row)),
+        None => return
+    };
+    let finish = move |row_editing: &RefCell<Option<RowEditing<I, U>>>, errors: Vec<(U, String)>, changes: Vec<CellEdit<I, U>>| {
+        if errors.is_empty() {
+            *row_editing.borrow_mut() = None;
+            if !changes.is_empty() {
+                on_row_edit_commit.emit(RowEdit { row_id, changes });
+            }
+        } else if let Some(row_edit) = row_editing.borrow_mut().as_mut() {
+            row_edit.errors = errors;
+            row_edit.pending = false;
+        }
+    };
+    let errors = validate_changes(row, &changes);
+    let validations: Vec<(U, ValidationFuture)> = changes.iter()
+        .filter_map(|edit| edit.column.validate_async(row, &edit.new_value).map(|validation| (edit.column, validation)))
+        .collect();
+    if !errors.is_empty() || validations.is_empty() {
+        finish(&row_editing, errors, changes);
+        force_update.force_update();
+        return;
+    }
+    if let Some(row_edit) = row_editing.borrow_mut().as_mut() {
+        row_edit.pending = true;
+    }
+    force_update.force_update();
+    let session = *edit_session.borrow();
+    spawn_local(async move {
+        let mut errors = Vec::new();
+        for (column, validation) in validations {
+            if let Err(error) = validation.await {
+                errors.push((column, error));
+            }
+        }
+        if *edit_session.borrow() != session {
+            return;
+        }
+        finish(&row_editing, errors, changes);
+        force_update.force_update();
+    });
+}
+
+/// Header menu entries for grouping by the column and expanding or collapsing every group
+fn group_menu_items<U: PartialEq + Copy + 'static>(column: U,
+                                                  group_by: &[U],
+                                                  on_group_by_change: &Callback<Vec<U>>,
+                                                  group_expansion: &UseStateHandle<GroupExpansion>) -> Vec<HeaderMenuItem> {
+    let mut items = Vec::new();
+    let mut new_group_by = group_by.to_vec();
+    let label = if group_by.contains(&column) {
+        new_group_by.retain(|c| *c != column);
+        "Ungroup column"
+    } else {
+        new_group_by.push(column);
+        "Group by column"
+    };
+    items.push(HeaderMenuItem::new(label, on_group_by_change.reform(move |_| new_group_by.clone())));
+    if !group_by.is_empty() {
+        for (label, expand) in [("Expand all groups", true), ("Collapse all groups", false)] {
+            let group_expansion = group_expansion.clone();
+            items.push(HeaderMenuItem::new(label, Callback::from(move |_| {
+                let mut expansion = (*group_expansion).clone();
+                if expand {
+                    expansion.expand_all();
+                } else {
+                    expansion.collapse_all();
+                }
+                group_expansion.set(expansion);
+            })));
+        }
+    }
+    items
+}
+
+/// Built-in header menu entries, apps append their own with `Props::header_menu_items`
+fn column_menu_items<U: PartialEq + Copy + 'static>(column: U,
+                                                   config: &GridDataColumnProps,
+                                                   autosize: i32,
+                                                   sort: &UseStateHandle<Option<SortModel<U>>>,
+                                                   column_state: &UseStateHandle<ColumnState<U>>,
+                                                   visible_count: usize,
+                                                   filter_editor: &UseStateHandle<Option<U>>) -> Vec<HeaderMenuItem> {
+    let mut items = Vec::new();
+    if config.sortable {
+        for (label, direction) in [("Sort ascending", SortDirection::Asc), ("Sort descending", SortDirection::Desc)] {
+            let sort = sort.clone();
+            items.push(HeaderMenuItem::new(label, Callback::from(move |_| sort.set(Some(SortModel { column, direction })))));
+        }
+        if sort.is_some_and(|s| s.column == column) {
+            let sort = sort.clone();
+            items.push(HeaderMenuItem::new("Clear sort", Callback::from(move |_| sort.set(None))));
+        }
+    }
+    {
+        let filter_editor = filter_editor.clone();
+        items.push(HeaderMenuItem::new("Filter", Callback::from(move |_| filter_editor.set(Some(column)))));
+    }
+    let update_columns = |label: &'static str, update: fn(&mut ColumnState<U>, U)| {
+        let column_state = column_state.clone();
+        HeaderMenuItem::new(label, Callback::from(move |_| {
+            let mut new_state = (*column_state).clone();
+            update(&mut new_state, column);
+            column_state.set(new_state);
+        }))
+    };
+    // the last column's header is the only way back to "Show all columns"
+    items.push(update_columns("Hide column", ColumnState::hide).disabled(visible_count <= 1));
+    let pin_label = if column_state.is_pinned(&column) { "Unpin column" } else { "Pin column" };
+    items.push(update_columns(pin_label, ColumnState::toggle_pinned));
+    {
+        let column_state = column_state.clone();
+        items.push(HeaderMenuItem::new("Autosize column", Callback::from(move |_| {
+            let mut new_state = (*column_state).clone();
+            new_state.set_width(column, autosize);
+            column_state.set(new_state);
+        })));
+    }
+    if !column_state.hidden.is_empty() {
+        items.push(update_columns("Show all columns", |state, _| state.hidden.clear()));
+    }
+    items
+}
+
 pub struct GridDataColumnProps {
     pub header_name: String,
     pub width: i32,
     pub editable: bool,
-    pub sortable: bool
+    pub sortable: bool,
+    /// Input shown when an editable cell is double clicked
+    pub editor: CellEditor,
+    /// Shown in group rows and the aggregate footer, computed over the filtered rows
+    pub aggregation: Option<Aggregation>
+}
+
+impl Default for GridDataColumnProps {
+    fn default() -> Self {
+        Self {
+            header_name: String::new(),
+            width: 100,
+            editable: false,
+            sortable: false,
+            editor: CellEditor::default(),
+            aggregation: None
+        }
+    }
+}
+
+/// Where a cell is being rendered, passed to `GridDataColumn::render_cell`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellContext<I> {
+    pub row_id: I,
+    /// Position of the row in the grid's current order, across all pages
+    pub row_index: usize,
+    pub col_index: usize,
+    pub selected: bool,
+    pub editing: bool
+}
+
+/// State of a header cell, passed to `GridDataColumn::render_header`
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeaderContext {
+    pub col_index: usize,
+    pub sort: Option<SortDirection>,
+    pub filtered: bool,
+    pub pinned: bool
 }
 
+/// Result of an asynchronous validation, e.g. a uniqueness check against a server
+pub type ValidationFuture = Pin<Box<dyn Future<Output=Result<(), String>>>>;
+
 pub trait GridDataColumn {
     type RowType;
     fn get_config(&self) -> GridDataColumnProps;
     fn get_value(&self, row: &Self::RowType) -> String;
+    /// Content of the cell, override to render badges, links, buttons etc. instead of the plain value
+    fn render_cell(&self, row: &Self::RowType, _ctx: &CellContext<<Self::RowType as GridData>::IdType>) -> Html
+        where Self::RowType: GridData
+    {
+        html! { self.get_value(row) }
+    }
+    /// Checks an edited value before it is committed, the error is shown on the cell
+    fn validate(&self, _row: &Self::RowType, _new_value: &str) -> Result<(), String> {
+        Ok(())
+    }
+    /// Runs after `validate` succeeds, the cell shows a pending state until the future resolves
+    fn validate_async(&self, _row: &Self::RowType, _new_value: &str) -> Option<ValidationFuture> {
+        None
+    }
+    /// Extra classes of the column's cell in `row`
+    fn get_cell_class(&self, _row: &Self::RowType) -> Classes {
+        Classes::new()
+    }
+    /// Extra inline style of the column's cell in `row`, e.g. `color: red;`
+    fn get_cell_style(&self, _row: &Self::RowType) -> Option<String> {
+        None
+    }
+    /// Checks a value read from an imported file, which has no row yet, rejected records are not imported
+    fn validate_import(&self, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+    /// Value written to JSON exports, override to export numbers, booleans or nulls rather than text
+    fn get_typed_value(&self, row: &Self::RowType) -> CellValue {
+        CellValue::Text(self.get_value(row))
+    }
+    /// Name of the column in exports keyed by `JsonKey::FieldKey`, defaults to the header name
+    fn get_field_key(&self) -> String {
+        self.get_config().header_name
+    }
+    /// Content of the header cell, the header menu button is rendered next to it
+    fn render_header(&self, ctx: &HeaderContext) -> Html {
+        let indicator = match ctx.sort {
+            Some(SortDirection::Asc) => " \u{25b2}",
+            Some(SortDirection::Desc) => " \u{25bc}",
+            None => ""
+        };
+        html! { format!("{}{indicator}", self.get_config().header_name) }
+    }
     fn get_field(&self) -> Self
         where Self: Sized + Copy
     {
@@ -154,36 +1613,82 @@ pub trait GridDataColumn {
 }
 
 pub trait GridData {
-    type IdType: Sized + Clone;
+    /// Identifies a row across renders, e.g. an integer key, a `Uuid` or a tuple for composite keys
+    type IdType: Clone + Eq + Hash + Debug + 'static;
     type ColumnType: GridDataColumn<RowType=Self>;
     fn get_value(&self, field: Self::ColumnType) -> String {
-        field.get_value(&self)
+        field.get_value(self)
+    }
+    fn get_id(&self) -> Self::IdType;
+    /// Id given to the `n`th row sharing `id` (`n` starts at 2) under `DuplicateIdPolicy::AutoSuffix`
+    fn suffixed_id(_id: &Self::IdType, _n: usize) -> Option<Self::IdType> {
+        None
     }
-    fn get_id(&self) -> String;
 }
 
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::test_support::{person, Field, Person};
+
+    #[test]
+    fn test_row_fn_compares_by_pointer() {
+        let height = RowFn::new(|row: &Person| (row.id == 1).then_some(80));
+        assert_eq!(height.call(&person(1, "Ada")), Some(80));
+        assert!(height == height.clone());
+        assert!(height != RowFn::new(|row: &Person| (row.id == 1).then_some(80)));
+    }
+
+    #[test]
+    fn test_page_heights_include_detail_panels() {
+        let page_view = vec![GroupedRow::Row(1), GroupedRow::Group { depth: 0, key: vec![], rows: vec![2], expanded: true }, GroupedRow::Row(2), GroupedRow::Row(3)];
+        let measured: HashMap<u32, i32> = [(2, 80)].into_iter().collect();
+        let details: HashMap<u32, i32> = [(1, 200), (2, 50)].into_iter().collect();
+        let heights = page_heights(&page_view, &[52, 52, 52, 52], &measured, &details);
+        // row 1's panel pushes every later entry down
+        assert_eq!(heights.offset(1), Some(252));
+        assert_eq!(heights.offset(2), Some(304));
+        assert_eq!(heights.offset(3), Some(434));
+        assert_eq!(page_heights(&page_view, &[52, 52, 52, 52], &HashMap::new(), &HashMap::new()).offset(3), Some(156));
+    }
+
+    #[test]
+    fn test_cell_context_row_id() {
+        let ctx = CellContext { row_id: 7, row_index: 0, col_index: 0, selected: false, editing: false };
+        assert_eq!(Field::Id.render_cell(&person(1, "Ada"), &ctx), html! { "#7" });
+        assert_eq!(Field::Name.render_cell(&person(1, "Ada"), &ctx), html! { "Ada" });
+    }
+
+    #[test]
+    fn test_check_editable() {
+        let rows = vec![GroupedRow::Group { depth: 0, key: vec!["a".to_string()], rows: vec![1], expanded: true }, GroupedRow::Row(1)];
+        let columns = [Field::Id, Field::Name];
+        assert_eq!(check_editable(&rows, &columns, &1, &Field::Name), Ok(()));
+        assert_eq!(check_editable(&rows, &columns, &2, &Field::Name), Err(GridError::RowNotFound("2".to_string())));
+        assert_eq!(check_editable(&rows, &columns, &1, &Field::Id), Err(GridError::ColumnNotEditable("Id".to_string())));
+        // hidden columns have no cell to edit
+        assert_eq!(check_editable(&rows, &columns, &1, &Field::Age), Err(GridError::ColumnNotEditable("Age".to_string())));
+    }
 
     #[test]
     fn example_cross_product() {
-        let rows = vec![1, 2 , 3];
-        let cols = vec![4, 5];
-        let c = rows.iter().map(|r| cols.iter().map(move |c| (r, c))).flatten();
+        let rows = [1, 2 , 3];
+        let cols = [4, 5];
+        let c = rows.iter().flat_map(|r| cols.iter().map(move |c| (r, c)));
         let d: Vec<(i32, i32)> = c.map(|(a, b)| (*a, *b)).collect();
         assert_eq!(d, vec![(1, 4), (1, 5), (2, 4), (2, 5), (3, 4), (3, 5)]);
 
-        let rows = vec![4, 5];
-        let cols = vec![1, 2 , 3];
-        let c = rows.iter().map(|r| cols.iter().map(move |c| (r, c))).flatten();
+        let rows = [4, 5];
+        let cols = [1, 2 , 3];
+        let c = rows.iter().flat_map(|r| cols.iter().map(move |c| (r, c)));
         let d: Vec<(i32, i32)> = c.map(|(a, b)| (*a, *b)).collect();
         assert_eq!(d, vec![(4, 1), (4, 2), (4, 3), (5, 1), (5, 2), (5, 3)]);
 
         // iterator with index (enumerate)
-        let rows = vec![1, 2 , 3];
-        let cols = vec![4, 5];
-        let c = rows.iter().enumerate().map(|(i, r)| cols.iter().map(move |c| (i, r, c))).flatten();
+        let rows = [1, 2 , 3];
+        let cols = [4, 5];
+        let c = rows.iter().enumerate().flat_map(|(i, r)| cols.iter().map(move |c| (i, r, c)));
         let d: Vec<(usize, i32, i32)> = c.map(|(a, b, c)| (a, *b, *c)).collect();
         assert_eq!(d, vec![(0, 1, 4), (0, 1, 5), (1, 2, 4), (1, 2, 5), (2, 3, 4), (2, 3, 5)]);
     }
@@ -0,0 +1,218 @@
+use std::fmt;
+use std::rc::Rc;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+/// Values and callbacks handed to an editor while a cell is being edited
+#[derive(Properties, Clone, PartialEq)]
+pub struct EditorProps {
+    /// Current value of the cell, or the pending draft after a rejected edit
+    pub value: AttrValue,
+    /// Class of the input, carries the grid's class prefix
+    pub class: Classes,
+    pub on_commit: Callback<String>,
+    pub on_cancel: Callback<()>
+}
+
+/// Renders the input used to edit a cell, implement this to plug in your own editor components
+pub trait Editor {
+    fn view(&self, props: &EditorProps) -> Html;
+}
+
+/// Editor used for a column, declared with `GridDataColumnProps::editor`
+#[derive(Clone, Default)]
+pub enum CellEditor {
+    #[default]
+    Text,
+    MultilineText,
+    Number { min: Option<f64>, max: Option<f64>, step: Option<f64> },
+    Select(Vec<String>),
+    /// Values are edited as "true" or "false"
+    Checkbox,
+    /// Values are edited as "YYYY-MM-DD"
+    Date,
+    Custom(Rc<dyn Editor>)
+}
+
+impl PartialEq for CellEditor {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CellEditor::Text, CellEditor::Text) => true,
+            (CellEditor::MultilineText, CellEditor::MultilineText) => true,
+            (CellEditor::Number { min, max, step }, CellEditor::Number { min: o_min, max: o_max, step: o_step }) => {
+                min == o_min && max == o_max && step == o_step
+            }
+            (CellEditor::Select(options), CellEditor::Select(other_options)) => options == other_options,
+            (CellEditor::Checkbox, CellEditor::Checkbox) => true,
+            (CellEditor::Date, CellEditor::Date) => true,
+            (CellEditor::Custom(editor), CellEditor::Custom(other_editor)) => Rc::ptr_eq(editor, other_editor),
+            _ => false
+        }
+    }
+}
+
+impl fmt::Debug for CellEditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellEditor::Text => write!(f, "Text"),
+            CellEditor::MultilineText => write!(f, "MultilineText"),
+            CellEditor::Number { min, max, step } => write!(f, "Number {{ min: {min:?}, max: {max:?}, step: {step:?} }}"),
+            CellEditor::Select(options) => write!(f, "Select({options:?})"),
+            CellEditor::Checkbox => write!(f, "Checkbox"),
+            CellEditor::Date => write!(f, "Date"),
+            CellEditor::Custom(_) => write!(f, "Custom")
+        }
+    }
+}
+
+impl Editor for CellEditor {
+    fn view(&self, props: &EditorProps) -> Html {
+        match self {
+            CellEditor::Text => input_editor("text", props, None),
+            CellEditor::MultilineText => multiline_text_editor(props),
+            CellEditor::Number { min, max, step } => input_editor("number", props, Some((*min, *max, *step))),
+            CellEditor::Select(options) => select_editor(options, props),
+            CellEditor::Checkbox => checkbox_editor(props),
+            CellEditor::Date => input_editor("date", props, None),
+            CellEditor::Custom(editor) => editor.view(props)
+        }
+    }
+}
+
+/// Enter commits and Escape cancels, shared by the single line editors
+fn on_editor_keydown(props: &EditorProps) -> Callback<KeyboardEvent> {
+    let on_commit = props.on_commit.clone();
+    let on_cancel = props.on_cancel.clone();
+    Callback::from(move |e: KeyboardEvent| {
+        match e.key().as_str() {
+            "Enter" => {
+                let input: HtmlInputElement = e.target_unchecked_into();
+                on_commit.emit(input.value());
+            }
+            "Escape" => on_cancel.emit(()),
+            _ => {}
+        }
+    })
+}
+
+fn input_editor(input_type: &'static str, props: &EditorProps, range: Option<(Option<f64>, Option<f64>, Option<f64>)>) -> Html {
+    let (min, max, step) = range.unwrap_or_default();
+    let onblur = {
+        let on_commit = props.on_commit.clone();
+        Callback::from(move |e: FocusEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_commit.emit(input.value());
+        })
+    };
+    html! {
+        <input class={props.class.clone()} type={input_type} value={props.value.clone()}
+            min={min.map(|v| v.to_string())} max={max.map(|v| v.to_string())} step={step.map(|v| v.to_string())}
+            onkeydown={on_editor_keydown(props)} {onblur}/>
+    }
+}
+
+fn multiline_text_editor(props: &EditorProps) -> Html {
+    // plain Enter adds a new line, Ctrl+Enter commits
+    let onkeydown = {
+        let on_commit = props.on_commit.clone();
+        let on_cancel = props.on_cancel.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            match e.key().as_str() {
+                "Enter" if e.ctrl_key() || e.meta_key() => {
+                    let input: HtmlTextAreaElement = e.target_unchecked_into();
+                    on_commit.emit(input.value());
+                }
+                "Escape" => on_cancel.emit(()),
+                _ => {}
+            }
+        })
+    };
+    let onblur = {
+        let on_commit = props.on_commit.clone();
+        Callback::from(move |e: FocusEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            on_commit.emit(input.value());
+        })
+    };
+    html! {
+        <textarea class={props.class.clone()} value={props.value.clone()} {onkeydown} {onblur}/>
+    }
+}
+
+fn select_editor(options: &[String], props: &EditorProps) -> Html {
+    let onchange = {
+        let on_commit = props.on_commit.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            on_commit.emit(select.value());
+        })
+    };
+    let onkeydown = {
+        let on_cancel = props.on_cancel.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Escape" {
+                on_cancel.emit(());
+            }
+        })
+    };
+    let onblur = {
+        let on_commit = props.on_commit.clone();
+        Callback::from(move |e: FocusEvent| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            on_commit.emit(select.value());
+        })
+    };
+    // a value that is not one of the options stays selected until another option is picked, so leaving
+    // the select without picking one commits the value unchanged
+    let current = (!options.iter().any(|option| *option == props.value.as_str())).then(|| html! {
+        <option value={props.value.clone()} selected=true hidden=true>{props.value.clone()}</option>
+    });
+    let options = options.iter().map(|option| {
+        html! {
+            <option value={option.clone()} selected={*option == props.value.as_str()}>{option}</option>
+        }
+    }).collect::<Html>();
+    html! {
+        <select class={props.class.clone()} {onchange} {onkeydown} {onblur}>{current}{options}</select>
+    }
+}
+
+/// "true" or "false" for a checkbox that was toggled, otherwise the value it was opened with, which can be neither
+fn checkbox_value(input: &HtmlInputElement, value: &AttrValue) -> String {
+    if input.checked() == (value.as_str() == "true") {
+        value.to_string()
+    } else {
+        input.checked().to_string()
+    }
+}
+
+fn checkbox_editor(props: &EditorProps) -> Html {
+    let onchange = {
+        let on_commit = props.on_commit.clone();
+        let value = props.value.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_commit.emit(checkbox_value(&input, &value));
+        })
+    };
+    let onkeydown = {
+        let on_cancel = props.on_cancel.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Escape" {
+                on_cancel.emit(());
+            }
+        })
+    };
+    let onblur = {
+        let on_commit = props.on_commit.clone();
+        let value = props.value.clone();
+        Callback::from(move |e: FocusEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_commit.emit(checkbox_value(&input, &value));
+        })
+    };
+    html! {
+        <input class={props.class.clone()} type="checkbox" checked={props.value.as_str() == "true"}
+            {onchange} {onkeydown} {onblur}/>
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// Problems the grid found in its props, reported through the `on_error` callback,
+/// or in a `GridApi` call, returned from the call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// Debug formatted ids shared by more than one row, each listed once in order of first appearance
+    DuplicateRowIds(Vec<String>),
+    /// Debug formatted ids of rows left out under `DuplicateIdPolicy::AutoSuffix` because no unused suffixed id
+    /// was found for them, each listed once
+    UnresolvedRowIds(Vec<String>),
+    /// Debug formatted id of a row that is not among the filtered rows
+    RowNotFound(String),
+    /// Header name of a column that is hidden or not editable
+    ColumnNotEditable(String),
+    /// The api was called before a grid rendered with it as its `api` prop
+    GridNotRendered,
+    /// A worksheet name Excel refuses: empty, longer than 31 characters, containing one of `[ ] : * ? / \`
+    /// or starting or ending with an apostrophe
+    InvalidSheetName(String)
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::DuplicateRowIds(ids) => write!(f, "duplicate row ids: {}", ids.join(", ")),
+            GridError::UnresolvedRowIds(ids) => write!(f, "no unique id found for rows with ids: {}", ids.join(", ")),
+            GridError::RowNotFound(id) => write!(f, "no row with id {id} is shown"),
+            GridError::ColumnNotEditable(column) => write!(f, "column {column} can't be edited"),
+            GridError::GridNotRendered => write!(f, "no grid has rendered with this api"),
+            GridError::InvalidSheetName(name) => write!(f, "{name:?} is not a valid worksheet name")
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
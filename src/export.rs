@@ -0,0 +1,363 @@
+use std::cell::RefCell;
+use std::hash::Hash;
+use std::rc::Rc;
+use js_sys::{Array, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::closure::Closure;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+use crate::data_grid::GridDataColumn;
+use crate::row_state::{RowState, Rows};
+
+/// Which rows of the grid's current view get exported, always in the view's sort order
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ExportScope {
+    /// Every row that passes the filters, across all pages
+    #[default]
+    All,
+    CurrentPage,
+    Selected
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    /// Starts the file with a UTF-8 byte order mark so spreadsheet apps detect the encoding
+    pub bom: bool,
+    pub scope: ExportScope,
+    /// Name of the file downloaded from the toolbar
+    pub file_name: String
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self { delimiter: ',', bom: false, scope: ExportScope::All, file_name: "export.csv".to_string() }
+    }
+}
+
+/// Layout of a JSON export
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum JsonFormat {
+    /// A single array holding an object per row
+    #[default]
+    Array,
+    /// One object per line, for line based pipelines
+    Ndjson
+}
+
+impl JsonFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            JsonFormat::Array => "application/json",
+            JsonFormat::Ndjson => "application/x-ndjson"
+        }
+    }
+}
+
+/// What names the fields of the exported objects
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum JsonKey {
+    #[default]
+    HeaderName,
+    /// `GridDataColumn::get_field_key`, stays the same when header names are renamed or translated
+    FieldKey
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonOptions {
+    pub format: JsonFormat,
+    pub key: JsonKey,
+    pub scope: ExportScope,
+    /// Name of the file downloaded from the toolbar
+    pub file_name: String
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        Self { format: JsonFormat::Array, key: JsonKey::HeaderName, scope: ExportScope::All, file_name: "export.json".to_string() }
+    }
+}
+
+/// Typed value of a cell for exports that keep types, returned by `GridDataColumn::get_typed_value`
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String)
+}
+
+impl CellValue {
+    /// The value as a JSON literal, numbers that JSON cannot represent become `null`
+    pub fn to_json(&self) -> String {
+        match self {
+            CellValue::Null => "null".to_string(),
+            CellValue::Bool(value) => value.to_string(),
+            CellValue::Number(value) if value.is_finite() => value.to_string(),
+            CellValue::Number(_) => "null".to_string(),
+            CellValue::Text(value) => json_string(value)
+        }
+    }
+}
+
+impl From<bool> for CellValue {
+    fn from(value: bool) -> Self {
+        CellValue::Bool(value)
+    }
+}
+
+impl From<f64> for CellValue {
+    fn from(value: f64) -> Self {
+        CellValue::Number(value)
+    }
+}
+
+impl From<i64> for CellValue {
+    fn from(value: i64) -> Self {
+        CellValue::Number(value as f64)
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(value: String) -> Self {
+        CellValue::Text(value)
+    }
+}
+
+impl From<&str> for CellValue {
+    fn from(value: &str) -> Self {
+        CellValue::Text(value.to_string())
+    }
+}
+
+impl<V: Into<CellValue>> From<Option<V>> for CellValue {
+    fn from(value: Option<V>) -> Self {
+        value.map_or(CellValue::Null, Into::into)
+    }
+}
+
+/// The grid's filtered rows in view order with what the export scopes need. The grid replaces its view on
+/// every render, the rows and their index are shared with it and only looked up when exporting.
+pub struct ExportView<I, T, U> {
+    pub rows: Rows<T>,
+    /// Index of `rows` by id
+    pub row_state: Rc<RefCell<RowState<I>>>,
+    /// Ids of the filtered rows in view order
+    pub view_ids: Rc<Vec<I>>,
+    pub page_ids: Vec<I>,
+    pub selected: Vec<I>,
+    /// Visible columns in display order
+    pub columns: Vec<U>
+}
+
+impl<I: Hash + Eq, T, U: GridDataColumn<RowType=T>> ExportView<I, T, U> {
+    /// Rows in the scope, in view order
+    pub fn rows(&self, scope: ExportScope) -> Vec<&T> {
+        let state = self.row_state.borrow();
+        scoped_ids(&self.view_ids, &self.page_ids, &self.selected, scope).into_iter()
+            .filter_map(|id| state.row_index_map.get(id).and_then(|i| self.rows.get(*i)))
+            .collect()
+    }
+
+    /// Rows of the current page with their ids, in page order
+    pub fn page_rows(&self) -> Vec<(&I, &T)> {
+        let state = self.row_state.borrow();
+        self.page_ids.iter()
+            .filter_map(|id| Some((id, state.row_index_map.get(id).and_then(|i| self.rows.get(*i))?)))
+            .collect()
+    }
+
+    pub fn to_csv(&self, options: &CsvOptions) -> String {
+        to_csv_with_options(self.rows(options.scope).into_iter(), &self.columns, options)
+    }
+
+    pub fn to_json(&self, options: &JsonOptions) -> String {
+        to_json(self.rows(options.scope).into_iter(), &self.columns, options)
+    }
+}
+
+/// Ids of `view_ids` in the scope, `page_ids` and `selected` are only used by their scopes
+pub fn scoped_ids<'a, I: PartialEq>(view_ids: &'a [I],
+                                    page_ids: &[I],
+                                    selected: &[I],
+                                    scope: ExportScope) -> Vec<&'a I> {
+    view_ids.iter()
+        .filter(|id| match scope {
+            ExportScope::All => true,
+            ExportScope::CurrentPage => page_ids.contains(id),
+            ExportScope::Selected => selected.contains(id)
+        })
+        .collect()
+}
+
+/// Quotes a field when it contains the delimiter, a quote or a line break (RFC 4180)
+fn csv_field(value: &str, delimiter: char) -> String {
+    if value.contains([delimiter, '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Serializes rows to CSV with a header line of the columns' header names
+pub fn to_csv<'a, T: 'a, U: GridDataColumn<RowType=T>>(rows: impl Iterator<Item=&'a T>, columns: &[U]) -> String {
+    to_csv_with_options(rows, columns, &CsvOptions::default())
+}
+
+pub fn to_csv_with_options<'a, T: 'a, U: GridDataColumn<RowType=T>>(rows: impl Iterator<Item=&'a T>,
+                                                                     columns: &[U],
+                                                                     options: &CsvOptions) -> String {
+    let delimiter = options.delimiter.to_string();
+    let mut lines = vec![columns.iter()
+        .map(|column| csv_field(&column.get_config().header_name, options.delimiter))
+        .collect::<Vec<String>>()
+        .join(&delimiter)];
+    for row in rows {
+        lines.push(columns.iter()
+            .map(|column| csv_field(&column.get_value(row), options.delimiter))
+            .collect::<Vec<String>>()
+            .join(&delimiter));
+    }
+    let mut csv = if options.bom { "\u{feff}".to_string() } else { String::new() };
+    csv.push_str(&lines.join("\r\n"));
+    csv.push_str("\r\n");
+    csv
+}
+
+/// Quotes and escapes a string as a JSON string literal
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c)
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Serializes rows to JSON objects with a field per column, using the columns' typed values
+pub fn to_json<'a, T: 'a, U: GridDataColumn<RowType=T>>(rows: impl Iterator<Item=&'a T>,
+                                                        columns: &[U],
+                                                        options: &JsonOptions) -> String {
+    let keys: Vec<String> = columns.iter()
+        .map(|column| json_string(&match options.key {
+            JsonKey::HeaderName => column.get_config().header_name,
+            JsonKey::FieldKey => column.get_field_key()
+        }))
+        .collect();
+    let objects = rows.map(|row| {
+        let fields: Vec<String> = keys.iter().zip(columns)
+            .map(|(key, column)| format!("{key}:{}", column.get_typed_value(row).to_json()))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    });
+    match options.format {
+        JsonFormat::Array => format!("[{}]", objects.collect::<Vec<String>>().join(",")),
+        JsonFormat::Ndjson => objects.map(|object| object + "\n").collect()
+    }
+}
+
+/// How long the object url of a download is kept before it is revoked
+const DOWNLOAD_URL_LIFETIME_MS: i32 = 1000;
+
+/// Saves `content` as a file through a temporary link, only works in the browser
+pub fn download(file_name: &str, mime_type: &str, content: &[u8]) -> Result<(), JsValue> {
+    let parts = Array::of1(&Uint8Array::from(content));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window to download from"))?;
+    let document = window.document().ok_or_else(|| JsValue::from_str("no document to download from"))?;
+    let body = document.body().ok_or_else(|| JsValue::from_str("no document body to download from"))?;
+    let link: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.set_attribute("style", "display: none")?;
+    // some browsers, Firefox among them, ignore clicks on links that are not in the document
+    body.append_child(&link)?;
+    link.click();
+    link.remove();
+    // revoked once the browser has started the download rather than while the click is handled
+    let revoke = Closure::once_into_js(move || {
+        if let Err(e) = Url::revoke_object_url(&url) {
+            log::error!("revoking the download url failed: {e:?}");
+        }
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), DOWNLOAD_URL_LIFETIME_MS)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{person, Field, Person};
+
+    fn notes(notes: &[(&str, &str)]) -> Vec<Person> {
+        notes.iter().enumerate().map(|(id, (name, note))| person(id as u32, name).note(note)).collect()
+    }
+
+    #[test]
+    fn test_to_csv_quotes_fields() {
+        let rows = notes(&[("Ada", "said \"hi\""), ("Grace", "line\nbreak"), ("Alan", "plain")]);
+        let csv = to_csv(rows.iter(), &[Field::Name, Field::Note]);
+        assert_eq!(csv, "Name,\"Note, long\"\r\nAda,\"said \"\"hi\"\"\"\r\nGrace,\"line\nbreak\"\r\nAlan,plain\r\n");
+    }
+
+    #[test]
+    fn test_csv_options() {
+        let rows = notes(&[("Ada", "a;b"), ("Grace", "c, d")]);
+        let options = CsvOptions { delimiter: ';', bom: true, ..Default::default() };
+        // columns are written in the order given, as the grid passes its visible columns
+        let csv = to_csv_with_options(rows.iter(), &[Field::Note, Field::Name], &options);
+        assert_eq!(csv, "\u{feff}Note, long;Name\r\n\"a;b\";Ada\r\nc, d;Grace\r\n");
+    }
+
+    #[test]
+    fn test_scoped_ids() {
+        let view_ids = [3, 1, 2];
+        assert_eq!(scoped_ids(&view_ids, &[3], &[2, 3], ExportScope::All), vec![&3, &1, &2]);
+        assert_eq!(scoped_ids(&view_ids, &[3], &[2, 3], ExportScope::CurrentPage), vec![&3]);
+        // selected rows keep the view order rather than the selection order
+        assert_eq!(scoped_ids(&view_ids, &[3], &[2, 3], ExportScope::Selected), vec![&3, &2]);
+    }
+
+    #[test]
+    fn test_export_view_reads_rows_by_id() {
+        let rows = notes(&[("Ada", "a"), ("Grace", "b"), ("Alan", "c")]);
+        let row_state = RowState::from_rows(&rows, Default::default());
+        let view = ExportView {
+            rows: rows.into(),
+            row_state: Rc::new(RefCell::new(row_state)),
+            view_ids: Rc::new(vec![2, 0]),
+            page_ids: vec![2],
+            selected: vec![0, 1],
+            columns: vec![Field::Name]
+        };
+        assert_eq!(view.to_csv(&CsvOptions::default()), "Name\r\nAlan\r\nAda\r\n");
+        // rows filtered out of the view are not exported even when selected
+        assert_eq!(view.to_csv(&CsvOptions { scope: ExportScope::Selected, ..Default::default() }), "Name\r\nAda\r\n");
+        assert_eq!(view.page_rows(), vec![(&2, &view.rows[2])]);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let rows = notes(&[("Ada \"L\"", "42"), ("Grace", ""), ("Alan", "a\nb")]);
+        let json = to_json(rows.iter(), &[Field::Name, Field::Note], &JsonOptions::default());
+        assert_eq!(json, r#"[{"Name":"Ada \"L\"","Note, long":42},{"Name":"Grace","Note, long":null},{"Name":"Alan","Note, long":"a\nb"}]"#);
+
+        let options = JsonOptions { format: JsonFormat::Ndjson, key: JsonKey::FieldKey, ..Default::default() };
+        let ndjson = to_json(rows[..2].iter(), &[Field::Name, Field::Note], &options);
+        assert_eq!(ndjson, "{\"name\":\"Ada \\\"L\\\"\",\"note\":42}\n{\"name\":\"Grace\",\"note\":null}\n");
+        assert_eq!(to_json([].iter(), &[Field::Name], &JsonOptions::default()), "[]");
+        assert_eq!(CellValue::Number(f64::NAN).to_json(), "null");
+        assert_eq!(CellValue::from("\u{1}").to_json(), "\"\\u0001\"");
+    }
+}
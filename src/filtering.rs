@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::data_grid::GridDataColumn;
+
+/// Case insensitive "contains" filter on a column's value
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnFilter<U> {
+    pub column: U,
+    pub value: String
+}
+
+impl<U> ColumnFilter<U> {
+    pub fn matches(&self, cell_value: &str) -> bool {
+        self.value.is_empty() || cell_value.to_lowercase().contains(&self.value.to_lowercase())
+    }
+}
+
+/// Row ids whose values match every filter, in their original order
+pub fn filter_row_ids<I, T, U>(ids: &[I],
+                               rows: &[T],
+                               row_index_map: &HashMap<I, usize>,
+                               filters: &[ColumnFilter<U>]) -> Vec<I>
+    where I: Hash + Eq + Clone, U: GridDataColumn<RowType=T>
+{
+    ids.iter().filter(|id| {
+        let row = &rows[row_index_map[*id]];
+        filters.iter().all(|filter| filter.matches(&filter.column.get_value(row)))
+    }).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{people, Field};
+
+    #[test]
+    fn test_filter_row_ids() {
+        let rows = people(&["Apple", "banana", "Pineapple"]);
+        let row_index_map: HashMap<usize, usize> = (0..rows.len()).map(|i| (i, i)).collect();
+        let ids: Vec<usize> = (0..rows.len()).collect();
+        let filters = vec![ColumnFilter { column: Field::Name, value: "APPLE".to_string() }];
+        assert_eq!(filter_row_ids(&ids, &rows, &row_index_map, &filters), vec![0, 2]);
+        let filters = vec![ColumnFilter { column: Field::Name, value: "".to_string() }];
+        assert_eq!(filter_row_ids(&ids, &rows, &row_index_map, &filters), ids);
+    }
+}
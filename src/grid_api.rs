@@ -0,0 +1,160 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::prelude::*;
+use crate::data_grid::GridData;
+use crate::error::GridError;
+use crate::export::{CsvOptions, JsonOptions};
+use crate::row_state::{RowDiff, RowTransaction};
+#[cfg(feature = "xlsx")]
+use crate::xlsx::XlsxOptions;
+
+/// Callbacks the grid registers on every render so the handle always acts on its latest state
+pub(crate) struct GridApiHandlers<I, U> {
+    pub undo: Callback<()>,
+    pub redo: Callback<()>,
+    pub scroll_to_row: Callback<I>,
+    pub scroll_to_column: Callback<U>,
+    pub go_to_page: Callback<i32>,
+    pub select_rows: Callback<Vec<I>>,
+    pub start_editing: Callback<(I, U), Result<(), GridError>>,
+    pub get_sorted_ids: Callback<(), Vec<I>>,
+    pub get_filtered_ids: Callback<(), Vec<I>>,
+    pub export_csv: Callback<CsvOptions, String>,
+    pub export_json: Callback<JsonOptions, String>,
+    #[cfg(feature = "xlsx")]
+    pub export_xlsx: Callback<XlsxOptions, Result<Vec<u8>, GridError>>,
+    pub row_offset: Callback<I, Option<i32>>,
+    pub print: Callback<()>,
+    pub refresh: Callback<()>,
+    pub apply_transaction: Callback<RowDiff<I>>
+}
+
+/// Handle for driving a `DataGrid` from its parent, create it with `use_grid_api` and pass it as the `api` prop.
+/// Calls made before the grid has rendered do nothing, or return `GridError::GridNotRendered`.
+pub struct GridApi<I, U> {
+    handlers: Rc<RefCell<Option<GridApiHandlers<I, U>>>>
+}
+
+impl<I, U> GridApi<I, U> {
+    pub(crate) fn register(&self, handlers: GridApiHandlers<I, U>) {
+        self.handlers.replace(Some(handlers));
+    }
+
+    fn call<R: Default>(&self, f: impl FnOnce(&GridApiHandlers<I, U>) -> R) -> R {
+        self.handlers.borrow().as_ref().map(f).unwrap_or_default()
+    }
+
+    /// Reverts the last edit transaction, the reverted values are sent to the edit callback it was committed through
+    pub fn undo(&self) {
+        self.call(|h| h.undo.emit(()))
+    }
+
+    pub fn redo(&self) {
+        self.call(|h| h.redo.emit(()))
+    }
+
+    /// Goes to the page containing the row and scrolls it into view, rows hidden by a filter are ignored
+    pub fn scroll_to_row(&self, id: I) {
+        self.call(|h| h.scroll_to_row.emit(id))
+    }
+
+    pub fn scroll_to_column(&self, column: U) {
+        self.call(|h| h.scroll_to_column.emit(column))
+    }
+
+    /// Pages out of range go to the first or last page, as in the pagination bar
+    pub fn go_to_page(&self, page: i32) {
+        self.call(|h| h.go_to_page.emit(page))
+    }
+
+    /// Replaces the selection, `on_selection_change` is called with the new ids
+    pub fn select_rows(&self, ids: Vec<I>) {
+        self.call(|h| h.select_rows.emit(ids))
+    }
+
+    /// Opens the editor of a cell, or of the whole row in row edit mode. Rows hidden by a filter and
+    /// hidden or read only columns are not edited and return an error.
+    pub fn start_editing(&self, id: I, column: U) -> Result<(), GridError> {
+        self.handlers.borrow().as_ref().map_or(Err(GridError::GridNotRendered), |h| h.start_editing.emit((id, column)))
+    }
+
+    /// Ids of every row in the current sort order
+    pub fn get_sorted_ids(&self) -> Vec<I> {
+        self.call(|h| h.get_sorted_ids.emit(()))
+    }
+
+    /// Ids of the rows that pass the current filters, in sort order
+    pub fn get_filtered_ids(&self) -> Vec<I> {
+        self.call(|h| h.get_filtered_ids.emit(()))
+    }
+
+    /// The filtered rows and visible columns as CSV
+    pub fn export_csv(&self) -> String {
+        self.export_csv_with_options(CsvOptions::default())
+    }
+
+    /// The rows in the options' scope and the visible columns as CSV, in the grid's current order
+    pub fn export_csv_with_options(&self, options: CsvOptions) -> String {
+        self.call(|h| h.export_csv.emit(options))
+    }
+
+    /// The rows in the options' scope as JSON or NDJSON, using the columns' typed values
+    pub fn export_json(&self, options: JsonOptions) -> String {
+        self.call(|h| h.export_json.emit(options))
+    }
+
+    /// The rows in the options' scope as an Excel workbook, save it with `export::download` and `xlsx::XLSX_MIME_TYPE`.
+    /// Fails when the sheet name is not valid in Excel.
+    #[cfg(feature = "xlsx")]
+    pub fn export_xlsx(&self, options: XlsxOptions) -> Result<Vec<u8>, GridError> {
+        self.handlers.borrow().as_ref().map_or(Err(GridError::GridNotRendered), |h| h.export_xlsx.emit(options))
+    }
+
+    /// Distance from the top of the current page to the row, using the measured heights in auto height mode
+    /// and counting the expanded detail panels above the row
+    pub fn row_offset(&self, id: I) -> Option<i32> {
+        self.call(|h| h.row_offset.emit(id))
+    }
+
+    /// Renders every filtered row and opens the browser's print dialog, the pages come back once it closes
+    pub fn print(&self) {
+        self.call(|h| h.print.emit(()))
+    }
+
+    /// Rebuilds the grid's row index from the `rows` prop and re-renders
+    pub fn refresh(&self) {
+        self.call(|h| h.refresh.emit(()))
+    }
+
+    /// Applies the transaction to `rows`, the parent's copy of the `rows` prop. When the grid next renders with
+    /// the result it patches its row index from the transaction's ids instead of reading every row's id.
+    pub fn apply_transaction<T: GridData<IdType=I>>(&self, rows: &mut Vec<T>, transaction: RowTransaction<T>) {
+        let diff = transaction.apply(rows);
+        self.call(|h| h.apply_transaction.emit(diff))
+    }
+}
+
+impl<I, U> Default for GridApi<I, U> {
+    fn default() -> Self {
+        Self { handlers: Rc::new(RefCell::new(None)) }
+    }
+}
+
+impl<I, U> Clone for GridApi<I, U> {
+    fn clone(&self) -> Self {
+        Self { handlers: self.handlers.clone() }
+    }
+}
+
+impl<I, U> PartialEq for GridApi<I, U> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.handlers, &other.handlers)
+    }
+}
+
+#[hook]
+pub fn use_grid_api<I, U>() -> GridApi<I, U>
+    where I: 'static, U: 'static
+{
+    (*use_memo(|_| GridApi::default(), ())).clone()
+}
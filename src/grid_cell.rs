@@ -1,5 +1,6 @@
 use yew::prelude::*;
 
+#[allow(dead_code)]
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
     key: String,
@@ -0,0 +1,91 @@
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+use yew::prelude::*;
+use crate::stylesheet::ClassPrefix;
+use crate::editors::{CellEditor, Editor, EditorProps};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub editor: CellEditor,
+    pub value: AttrValue,
+    #[prop_or_default]
+    pub error: Option<AttrValue>,
+    #[prop_or_default]
+    pub pending: bool,
+    #[prop_or(true)]
+    pub autofocus: bool,
+    /// Editors in row edit mode report every value and stay open until the row is saved
+    #[prop_or_default]
+    pub keep_open: bool,
+    pub on_commit: Callback<String>,
+    pub on_cancel: Callback<()>
+}
+
+/// Hosts a column's editor, focuses it when editing starts and makes sure it finishes only once
+#[function_component(GridCellEditor)]
+pub fn grid_cell_editor(props: &Props) -> Html {
+    let prefix = use_context::<ClassPrefix>().unwrap_or_default();
+    let container = use_node_ref();
+    // removing a focused input fires blur, which would commit a second time after Enter or Escape
+    let finished = use_mut_ref(|| false);
+
+    {
+        let container = container.clone();
+        let autofocus = props.autofocus;
+        use_effect_with_deps(move |_| {
+            if !autofocus {
+                return;
+            }
+            let editor = container.cast::<HtmlElement>()
+                .and_then(|div| div.query_selector("input, select, textarea").ok().flatten())
+                .and_then(|element| element.dyn_into::<HtmlElement>().ok());
+            if let Some(editor) = editor {
+                let _ = editor.focus();
+            }
+        }, ());
+    }
+
+    if props.error.is_some() && !props.pending {
+        // a rejected value can be corrected and committed again
+        finished.replace(false);
+    }
+
+    let on_commit = {
+        let finished = finished.clone();
+        let on_commit = props.on_commit.clone();
+        let keep_open = props.keep_open;
+        Callback::from(move |value: String| {
+            if keep_open || !finished.replace(true) {
+                on_commit.emit(value);
+            }
+        })
+    };
+    let on_cancel = {
+        let on_cancel = props.on_cancel.clone();
+        Callback::from(move |_| {
+            if !finished.replace(true) {
+                on_cancel.emit(());
+            }
+        })
+    };
+    let editor_props = EditorProps {
+        value: props.value.clone(),
+        class: classes!(prefix.class("yew-data-grid-cell-editor")),
+        on_commit,
+        on_cancel
+    };
+    let error = props.error.as_ref().map(|error| html! {
+        <div class={prefix.class("yew-data-grid-cell-error")}>{error.clone()}</div>
+    });
+    let pending = props.pending.then(|| html! {
+        <span class={prefix.class("yew-data-grid-cell-editor-pending")}>{"\u{2026}"}</span>
+    });
+    let class = classes!(prefix.class("yew-data-grid-cell-editor-container"), props.pending.then(|| prefix.class("yew-data-grid-cell-editor-container-pending")));
+    html! {
+        <div {class} ref={container} title={props.error.clone()}>
+            {props.editor.view(&editor_props)}
+            {pending}
+            {error}
+        </div>
+    }
+}
@@ -0,0 +1,180 @@
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DragEvent, File, HtmlInputElement, HtmlSelectElement};
+use yew::platform::spawn_local;
+use yew::prelude::*;
+use crate::stylesheet::ClassPrefix;
+use crate::data_grid::GridDataColumn;
+use crate::import::{CsvImport, CsvImportOptions, ImportedRow};
+
+/// Records shown in the preview, every record is still imported
+const PREVIEW_RECORDS: usize = 20;
+
+#[derive(Properties, PartialEq)]
+pub struct Props<U: GridDataColumn + Clone + PartialEq> {
+    /// Grid columns the file's columns can be mapped onto
+    pub columns: Vec<U>,
+    #[prop_or_default]
+    pub options: CsvImportOptions,
+    /// Receives the records without validation errors
+    pub on_import: Callback<Vec<ImportedRow<U>>>,
+    pub on_close: Callback<()>
+}
+
+async fn read_file(file: File) -> Result<String, String> {
+    let text = JsFuture::from(file.text()).await.map_err(|e| format!("{e:?}"))?;
+    text.as_string().ok_or_else(|| "file is not text".to_string())
+}
+
+/// Dialog that reads a dropped or selected CSV file, maps its columns onto the grid's and previews the records
+#[function_component(GridCsvImport)]
+pub fn grid_csv_import<U: GridDataColumn + Clone + PartialEq + 'static>(props: &Props<U>) -> Html {
+    let prefix = use_context::<ClassPrefix>().unwrap_or_default();
+    let import = use_state(|| None::<CsvImport<U>>);
+    let read_error = use_state(|| None::<String>);
+    let dragging = use_state(|| false);
+
+    let load_file = {
+        let import = import.clone();
+        let read_error = read_error.clone();
+        let columns = props.columns.clone();
+        let options = props.options.clone();
+        Callback::from(move |file: File| {
+            let import = import.clone();
+            let read_error = read_error.clone();
+            let columns = columns.clone();
+            let options = options.clone();
+            spawn_local(async move {
+                match read_file(file).await {
+                    Ok(text) => {
+                        import.set(Some(CsvImport::parse(&text, &options, &columns)));
+                        read_error.set(None);
+                    }
+                    Err(e) => read_error.set(Some(format!("Could not read the file: {e}")))
+                }
+            });
+        })
+    };
+    let onchange = {
+        let load_file = load_file.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                load_file.emit(file);
+            }
+        })
+    };
+    let ondragover = {
+        let dragging = dragging.clone();
+        Callback::from(move |e: DragEvent| {
+            // allows the drop
+            e.prevent_default();
+            dragging.set(true);
+        })
+    };
+    let ondragleave = {
+        let dragging = dragging.clone();
+        Callback::from(move |_: DragEvent| dragging.set(false))
+    };
+    let ondrop = {
+        let dragging = dragging.clone();
+        Callback::from(move |e: DragEvent| {
+            e.prevent_default();
+            dragging.set(false);
+            if let Some(file) = e.data_transfer().and_then(|data| data.files()).and_then(|files| files.get(0)) {
+                load_file.emit(file);
+            }
+        })
+    };
+    let drop_class = classes!(prefix.class("yew-data-grid-import-drop"), dragging.then(|| prefix.class("yew-data-grid-import-drop-active")));
+    let drop_zone = html! {
+        <label class={drop_class} {ondragover} {ondragleave} {ondrop}>
+            {"Drop a CSV file here or choose one "}
+            <input type="file" accept=".csv,text/csv" {onchange}/>
+        </label>
+    };
+
+    let rows = import.as_ref().map(|import| import.rows()).unwrap_or_default();
+    let valid_rows: Vec<ImportedRow<U>> = rows.iter().filter(|row| row.is_valid()).cloned().collect();
+    let preview = import.as_ref().map(|current| {
+        let mapping_cells = current.headers.iter().enumerate().map(|(index, header)| {
+            let import = import.clone();
+            let columns = props.columns.clone();
+            let onchange = Callback::from(move |e: Event| {
+                let select: HtmlSelectElement = e.target_unchecked_into();
+                let column = select.value().parse::<usize>().ok().and_then(|i| columns.get(i).cloned());
+                if let Some(mut updated) = (*import).clone() {
+                    updated.set_mapping(index, column);
+                    import.set(Some(updated));
+                }
+            });
+            let mapped = current.mapping[index].clone();
+            let options = props.columns.iter().enumerate().map(|(i, column)| html! {
+                <option value={i.to_string()} selected={mapped.as_ref() == Some(column)}>
+                    {column.get_config().header_name}
+                </option>
+            }).collect::<Html>();
+            html! {
+                <th>
+                    <div class={prefix.class("yew-data-grid-import-header")}>{header}</div>
+                    <select {onchange}>
+                        <option value="" selected={mapped.is_none()}>{"(skip)"}</option>
+                        {options}
+                    </select>
+                </th>
+            }
+        }).collect::<Html>();
+        let preview_rows = rows.iter().take(PREVIEW_RECORDS).map(|row| {
+            let cells = current.mapping.iter().map(|column| match column {
+                Some(column) => {
+                    let error = row.error(column).map(str::to_string);
+                    let class = classes!(error.is_some().then(|| prefix.class("yew-data-grid-import-error")));
+                    html! { <td {class} title={error}>{row.value(column).unwrap_or_default()}</td> }
+                }
+                None => html! { <td class={prefix.class("yew-data-grid-import-skipped")}></td> }
+            }).collect::<Html>();
+            html! { <tr>{cells}</tr> }
+        }).collect::<Html>();
+        let invalid = rows.len() - valid_rows.len();
+        html! {
+            <>
+                <div class={prefix.class("yew-data-grid-import-preview")}>
+                    <table>
+                        <thead><tr>{mapping_cells}</tr></thead>
+                        <tbody>{preview_rows}</tbody>
+                    </table>
+                </div>
+                <div class={prefix.class("yew-data-grid-import-summary")}>
+                    {format!("{} records, {invalid} with errors that will be skipped", current.len())}
+                </div>
+            </>
+        }
+    });
+
+    let onclose = props.on_close.reform(|_| ());
+    let onimport = {
+        let on_import = props.on_import.clone();
+        let on_close = props.on_close.clone();
+        let valid_rows = valid_rows.clone();
+        Callback::from(move |_| {
+            on_import.emit(valid_rows.clone());
+            on_close.emit(());
+        })
+    };
+    html! {
+        <div class={prefix.class("yew-data-grid-import")}>
+            <div class={prefix.class("yew-data-grid-import-dialog")}>
+                {drop_zone}
+                if let Some(error) = &*read_error {
+                    <div class={classes!(prefix.class("yew-data-grid-import-summary"), prefix.class("yew-data-grid-import-error"))}>{error}</div>
+                }
+                {preview}
+                <div class={prefix.class("yew-data-grid-import-actions")}>
+                    <button class={prefix.class("yew-data-grid-toolbar-button")} onclick={onclose}>{"Cancel"}</button>
+                    <button class={prefix.class("yew-data-grid-toolbar-button")} onclick={onimport} disabled={valid_rows.is_empty()}>
+                        {format!("Import {} rows", valid_rows.len())}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,53 @@
+use yew::prelude::*;
+use crate::stylesheet::ClassPrefix;
+
+#[derive(Clone, PartialEq)]
+pub struct HeaderMenuItem {
+    pub label: AttrValue,
+    pub on_select: Callback<()>,
+    /// Shown but can't be selected
+    pub disabled: bool
+}
+
+impl HeaderMenuItem {
+    pub fn new(label: impl Into<AttrValue>, on_select: Callback<()>) -> Self {
+        Self { label: label.into(), on_select, disabled: false }
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub items: Vec<HeaderMenuItem>,
+    pub on_close: Callback<()>
+}
+
+#[function_component(GridHeaderMenu)]
+pub fn grid_header_menu(props: &Props) -> Html {
+    let prefix = use_context::<ClassPrefix>().unwrap_or_default();
+    let items = props.items.iter().map(|item| {
+        let onclick = {
+            let on_select = item.on_select.clone();
+            let on_close = props.on_close.clone();
+            Callback::from(move |e: MouseEvent| {
+                e.stop_propagation();
+                on_select.emit(());
+                on_close.emit(());
+            })
+        };
+        html! {
+            <button class={prefix.class("yew-data-grid-header-menu-item")} {onclick} disabled={item.disabled}>
+                {item.label.clone()}
+            </button>
+        }
+    }).collect::<Html>();
+    html! {
+        <div class={prefix.class("yew-data-grid-header-menu")}>
+            {items}
+        </div>
+    }
+}
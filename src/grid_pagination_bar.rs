@@ -1,4 +1,6 @@
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
+use crate::stylesheet::ClassPrefix;
 use crate::hooks::pagination::Pagination;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -18,143 +20,140 @@ fn get_page_range(i: i32, num_pages: i32, max_num_pages: i32) -> PageRange {
     }
 }
 
+/// A single button in the page list, ellipses carry the page they jump to
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PageButton {
+    Page(i32),
+    Ellipsis(i32)
+}
+
+fn get_page_buttons(page: i32, num_pages: i32, max_pages_to_show: i32) -> Vec<PageButton> {
+    if num_pages <= max_pages_to_show {
+        return (1..num_pages + 1).map(PageButton::Page).collect();
+    }
+    // ellipses skip a whole window of pages
+    let window = max_pages_to_show;
+    let back = PageButton::Ellipsis((page - window).max(1));
+    let forward = PageButton::Ellipsis((page + window).min(num_pages));
+    let mut buttons = Vec::new();
+    match get_page_range(page, num_pages, max_pages_to_show) {
+        PageRange::Lower => {
+            buttons.extend((1..max_pages_to_show + 1).map(PageButton::Page));
+            buttons.push(forward);
+            buttons.push(PageButton::Page(num_pages));
+        }
+        PageRange::Mid => {
+            let start = page - max_pages_to_show / 2;
+            let end = start + max_pages_to_show;
+            buttons.push(PageButton::Page(1));
+            buttons.push(back);
+            buttons.extend((start..end).map(PageButton::Page));
+            buttons.push(forward);
+            buttons.push(PageButton::Page(num_pages));
+        }
+        PageRange::Upper => {
+            buttons.push(PageButton::Page(1));
+            buttons.push(back);
+            buttons.extend((num_pages - max_pages_to_show + 1..num_pages + 1).map(PageButton::Page));
+        }
+    }
+    buttons
+}
+
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
     pub max_pages_to_show: i32,
-    pub pagination: UseStateHandle<Pagination>
+    pub pagination: Pagination,
+    pub on_page_change: Callback<i32>
 }
 
 #[function_component(GridPaginationBar)]
 pub fn grid_pagination_bar(props: &Props) -> Html {
+    let prefix = use_context::<ClassPrefix>().unwrap_or_default();
     let total_rows = props.pagination.total_rows;
-    let summary = format!("1 - {total_rows} of {total_rows}");
+    let first_row = if total_rows == 0 { 0 } else { (props.pagination.page - 1) as usize * props.pagination.page_size as usize + 1 };
+    let last_row = (props.pagination.page as usize * props.pagination.page_size as usize).min(total_rows);
+    let summary = format!("{first_row} - {last_row} of {total_rows}");
     const BAR_HEIGHT: i32 = 48;
     let style = format!("min-height: {BAR_HEIGHT}px; display: flex; flex-direction: row;");
 
-    let inc_page = {
-        let state = props.pagination.clone();
-        Callback::from(move |_| {
-            let mut new_state = Pagination::new(state.total_rows, state.page_size);
-            let new_page_num = if state.page + 1 > state.number_pages {
-                state.number_pages
-            } else {
-                state.page + 1
-            };
-            new_state.page = new_page_num;
-            state.set(new_state);
+    let jump_page = {
+        let pagination = props.pagination;
+        let on_page_change = props.on_page_change.clone();
+        Callback::from(move |page_num| {
+            let new_page_num = pagination.clamp_page(page_num);
+            if new_page_num != pagination.page {
+                on_page_change.emit(new_page_num);
+            }
         })
     };
 
+    let page = props.pagination.page;
+    let number_pages = props.pagination.number_pages;
+    let first_page = {
+        let jump_page = jump_page.clone();
+        Callback::from(move |_| jump_page.emit(1))
+    };
     let dec_page = {
-        let state = props.pagination.clone();
-        Callback::from(move |_| {
-            let mut new_state = Pagination::new(state.total_rows, state.page_size);
-            let new_page_num = if state.page - 1 < 1 {
-                1
-            } else {
-                state.page - 1
-            };
-            new_state.page = new_page_num;
-            state.set(new_state);
-        })
+        let jump_page = jump_page.clone();
+        Callback::from(move |_| jump_page.emit(page - 1))
     };
-
-    let jump_page = {
-        let state = props.pagination.clone();
-        Callback::from(move |page_num| {
-            let mut new_state = Pagination::new(state.total_rows, state.page_size);
-            let new_page_num = if page_num < 1 {
-                1
-            } else if page_num > state.number_pages {
-                state.number_pages
-            } else {
-                page_num
-            };
-            new_state.page = new_page_num;
-            state.set(new_state);
-        })
+    let inc_page = {
+        let jump_page = jump_page.clone();
+        Callback::from(move |_| jump_page.emit(page + 1))
     };
-
-    let page_buttons = {
-        if props.pagination.number_pages <= props.max_pages_to_show {
-            pages_view_uncapped(props, jump_page)
-        } else {
-            let page_range = get_page_range(props.pagination.page,
-                                            props.pagination.number_pages,
-                                            props.max_pages_to_show);
-            match page_range {
-                PageRange::Lower => pages_in_lower_range_view(props, jump_page),
-                PageRange::Mid => pages_fit_in_limit_view(props, jump_page),
-                PageRange::Upper => pages_fit_in_limit_view(props, jump_page)
+    let last_page = {
+        let jump_page = jump_page.clone();
+        Callback::from(move |_| jump_page.emit(number_pages))
+    };
+    let go_to_page = {
+        let jump_page = jump_page.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(page_num) = input.value().trim().parse::<i32>() {
+                jump_page.emit(page_num);
             }
-        }
+            input.set_value("");
+        })
     };
+
+    let page_buttons = get_page_buttons(page, number_pages, props.max_pages_to_show)
+        .into_iter()
+        .map(|button| page_button_view(button, page, jump_page.clone(), &prefix))
+        .collect::<Html>();
     html! {
-        <div class="yew-grid-pagination-bar" style={style}>
-            <div class="yew-grid-pagination-bar-controls">
-                <button onclick={dec_page} class="yew-grid-pagination-bar-control-button">{"<"}</button>
-                {{page_buttons}}
-                <button onclick={inc_page} class="yew-grid-pagination-bar-control-button">{">"}</button>
+        <div class={prefix.class("yew-grid-pagination-bar")} style={style}>
+            <div class={prefix.class("yew-grid-pagination-bar-controls")}>
+                <button onclick={first_page} class={prefix.class("yew-grid-pagination-bar-control-button")}>{"<<"}</button>
+                <button onclick={dec_page} class={prefix.class("yew-grid-pagination-bar-control-button")}>{"<"}</button>
+                {page_buttons}
+                <button onclick={inc_page} class={prefix.class("yew-grid-pagination-bar-control-button")}>{">"}</button>
+                <button onclick={last_page} class={prefix.class("yew-grid-pagination-bar-control-button")}>{">>"}</button>
+                <input type="number" class={prefix.class("yew-grid-pagination-bar-page-input")} placeholder="Go to page"
+                    min="1" max={number_pages.to_string()} onchange={go_to_page}/>
             </div>
-            <span class="yew-grid-pagination-bar-summary">{summary}</span>
+            <span class={prefix.class("yew-grid-pagination-bar-summary")}>{summary}</span>
         </div>
     }
 }
 
-fn pages_view_uncapped(props: &Props, jump_page: Callback<i32>) -> Html {
-    let num_pages = props.pagination.number_pages;
-    (1..num_pages+1).map(|i| {
-        let page = i.to_string();
-        let jump_page = jump_page.clone();
-        return if i == props.pagination.page {
-            html! {
-                <button class="yew-grid-pagination-bar-control-button yew-grid-pagination-bar-control-button-selected">{page}</button>
-            }
-        } else {
-            html! {
-                <button onclick={ move|_| {jump_page.emit(i)}} class="yew-grid-pagination-bar-control-button">{page}</button>
-            }
-        }
-    }).collect::<Html>()
-}
-
-fn pages_in_lower_range_view(props: &Props, jump_page: Callback<i32>) -> Html {
-    let max_pages_to_show = props.max_pages_to_show;
-    // show ellipses at second last page
-    (1..max_pages_to_show + 1).map(|i| {
-        let page = i.to_string();
-        let jump_page = jump_page.clone();
-        if i == max_pages_to_show - 1 {
-            html! {
-                <button class="yew-grid-pagination-bar-control-button yew-grid-pagination-bar-control-button-ellipsis">{"..."}</button>
-            }
-        } else if i == props.pagination.page {
-            html! {
-                <button class="yew-grid-pagination-bar-control-button yew-grid-pagination-bar-control-button-selected">{page}</button>
-            }
-        } else {
-            html! {
-                <button onclick={ move|_| {jump_page.emit(i)}} class="yew-grid-pagination-bar-control-button">{page}</button>
-            }
+fn page_button_view(button: PageButton, current_page: i32, jump_page: Callback<i32>, prefix: &ClassPrefix) -> Html {
+    match button {
+        PageButton::Ellipsis(target) => html! {
+            <button onclick={move |_| jump_page.emit(target)}
+                class={classes!(prefix.class("yew-grid-pagination-bar-control-button"), prefix.class("yew-grid-pagination-bar-control-button-ellipsis"))}>
+                {"..."}
+            </button>
+        },
+        PageButton::Page(i) if i == current_page => html! {
+            <button class={classes!(prefix.class("yew-grid-pagination-bar-control-button"), prefix.class("yew-grid-pagination-bar-control-button-selected"))}>
+                {i.to_string()}
+            </button>
+        },
+        PageButton::Page(i) => html! {
+            <button onclick={move |_| jump_page.emit(i)} class={prefix.class("yew-grid-pagination-bar-control-button")}>{i.to_string()}</button>
         }
-    }).collect::<Html>()
-}
-
-fn pages_fit_in_limit_view(props: &Props, jump_page: Callback<i32>) -> Html {
-    let num_pages = props.pagination.number_pages;
-    (1..num_pages+1).map(|i| {
-        let page = i.to_string();
-        let jump_page = jump_page.clone();
-        return if i == props.pagination.page {
-            html! {
-                <button class="yew-grid-pagination-bar-control-button yew-grid-pagination-bar-control-button-selected">{page}</button>
-            }
-        } else {
-            html! {
-                <button onclick={ move|_| {jump_page.emit(i)}} class="yew-grid-pagination-bar-control-button">{page}</button>
-            }
-        }
-    }).collect::<Html>()
+    }
 }
 
 #[cfg(test)]
@@ -162,6 +161,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(unused_variables)]
     fn test_get_page_range() {
         let max_num_pages = 10;
         let num_pages = 100;
@@ -178,4 +178,29 @@ mod tests {
         // ellipses shown at second page
         let page_range = get_page_range(91, num_pages, max_num_pages);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_get_page_buttons() {
+        use PageButton::*;
+        // everything fits
+        assert_eq!(get_page_buttons(2, 3, 10), vec![Page(1), Page(2), Page(3)]);
+
+        let lower = get_page_buttons(1, 5000, 10);
+        assert_eq!(lower.len(), 12);
+        assert_eq!(lower[10], Ellipsis(11));
+        assert_eq!(lower[11], Page(5000));
+
+        let mid = get_page_buttons(2500, 5000, 10);
+        assert_eq!(mid[0], Page(1));
+        assert_eq!(mid[1], Ellipsis(2490));
+        assert!(mid.contains(&Page(2500)));
+        assert_eq!(mid[mid.len() - 2], Ellipsis(2510));
+        assert_eq!(mid[mid.len() - 1], Page(5000));
+
+        let upper = get_page_buttons(4995, 5000, 10);
+        assert_eq!(upper[0], Page(1));
+        assert_eq!(upper[1], Ellipsis(4985));
+        assert_eq!(upper[2], Page(4991));
+        assert_eq!(upper[upper.len() - 1], Page(5000));
+    }
+}
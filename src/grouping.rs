@@ -0,0 +1,144 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use crate::data_grid::GridDataColumn;
+
+/// Values of the grouping columns down to a group, identifies the group for expanding and collapsing
+pub type GroupKey = Vec<String>;
+
+/// An entry of the grouped view, either a group header or a data row
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupedRow<I> {
+    /// Header of a group, `depth` is the position of its column in `group_by` and `rows` has every row
+    /// below it, including those of collapsed or nested groups
+    Group { key: GroupKey, depth: usize, rows: Vec<I>, expanded: bool },
+    Row(I)
+}
+
+/// Which groups are expanded, groups start expanded until collapsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupExpansion {
+    all_expanded: bool,
+    /// Groups in the opposite state of `all_expanded`
+    toggled: HashSet<GroupKey>
+}
+
+impl Default for GroupExpansion {
+    fn default() -> Self {
+        Self { all_expanded: true, toggled: HashSet::new() }
+    }
+}
+
+impl GroupExpansion {
+    pub fn is_expanded(&self, key: &GroupKey) -> bool {
+        self.all_expanded != self.toggled.contains(key)
+    }
+
+    pub fn toggle(&mut self, key: GroupKey) {
+        if !self.toggled.remove(&key) {
+            self.toggled.insert(key);
+        }
+    }
+
+    pub fn expand_all(&mut self) {
+        self.all_expanded = true;
+        self.toggled.clear();
+    }
+
+    pub fn collapse_all(&mut self) {
+        self.all_expanded = false;
+        self.toggled.clear();
+    }
+}
+
+/// Buckets the rows by the values of the `group_by` columns, outermost first. Groups appear in the order of
+/// their first row in `ids`, so they follow the sort model, and rows of collapsed groups are left out.
+pub fn group_rows<I, T, U>(ids: &[I],
+                           rows: &[T],
+                           row_index_map: &HashMap<I, usize>,
+                           group_by: &[U],
+                           expansion: &GroupExpansion) -> Vec<GroupedRow<I>>
+    where I: Hash + Eq + Clone, U: GridDataColumn<RowType=T>
+{
+    if group_by.is_empty() {
+        return ids.iter().cloned().map(GroupedRow::Row).collect();
+    }
+    let keyed: Vec<(GroupKey, &I)> = ids.iter()
+        .map(|id| (group_by.iter().map(|column| column.get_value(&rows[row_index_map[id]])).collect(), id))
+        .collect();
+    let members: Vec<usize> = (0..keyed.len()).collect();
+    let mut grouped = Vec::new();
+    push_groups(&keyed, &members, 0, expansion, &mut grouped);
+    grouped
+}
+
+fn push_groups<I: Clone>(keyed: &[(GroupKey, &I)],
+                         members: &[usize],
+                         depth: usize,
+                         expansion: &GroupExpansion,
+                         grouped: &mut Vec<GroupedRow<I>>) {
+    if members.first().is_some_and(|i| keyed[*i].0.len() == depth) {
+        grouped.extend(members.iter().map(|i| GroupedRow::Row(keyed[*i].1.clone())));
+        return;
+    }
+    let mut buckets: Vec<Vec<usize>> = Vec::new();
+    let mut bucket_index: HashMap<&str, usize> = HashMap::new();
+    for i in members {
+        let bucket = *bucket_index.entry(keyed[*i].0[depth].as_str()).or_insert_with(|| {
+            buckets.push(Vec::new());
+            buckets.len() - 1
+        });
+        buckets[bucket].push(*i);
+    }
+    for bucket in buckets {
+        let key: GroupKey = keyed[bucket[0]].0[..=depth].to_vec();
+        let expanded = expansion.is_expanded(&key);
+        let rows = bucket.iter().map(|i| keyed[*i].1.clone()).collect();
+        grouped.push(GroupedRow::Group { key, depth, rows, expanded });
+        if expanded {
+            push_groups(keyed, &bucket, depth + 1, expansion, grouped);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{person, Field, Person};
+
+    /// People in a team with their role as the note
+    fn members(members: &[(&str, &str)]) -> Vec<Person> {
+        members.iter().enumerate().map(|(id, (team, role))| person(id as u32, "").team(team).note(role)).collect()
+    }
+
+    fn group(key: &[&str], rows: &[usize], expanded: bool) -> GroupedRow<usize> {
+        let key: GroupKey = key.iter().map(|v| v.to_string()).collect();
+        GroupedRow::Group { depth: key.len() - 1, key, rows: rows.to_vec(), expanded }
+    }
+
+    #[test]
+    fn test_group_rows() {
+        let rows = members(&[("b", "dev"), ("a", "ops"), ("b", "ops"), ("b", "dev")]);
+        let row_index_map: HashMap<usize, usize> = (0..rows.len()).map(|i| (i, i)).collect();
+        let ids: Vec<usize> = (0..rows.len()).collect();
+        let mut expansion = GroupExpansion::default();
+
+        assert_eq!(group_rows(&ids, &rows, &row_index_map, &[Field::Team], &expansion), vec![
+            group(&["b"], &[0, 2, 3], true), GroupedRow::Row(0), GroupedRow::Row(2), GroupedRow::Row(3),
+            group(&["a"], &[1], true), GroupedRow::Row(1),
+        ]);
+
+        // nested groups hold every row below them, collapsed groups hide their rows
+        expansion.toggle(vec!["b".to_string(), "dev".to_string()]);
+        assert_eq!(group_rows(&ids, &rows, &row_index_map, &[Field::Team, Field::Note], &expansion), vec![
+            group(&["b"], &[0, 2, 3], true),
+            group(&["b", "dev"], &[0, 3], false),
+            group(&["b", "ops"], &[2], true), GroupedRow::Row(2),
+            group(&["a"], &[1], true),
+            group(&["a", "ops"], &[1], true), GroupedRow::Row(1),
+        ]);
+
+        expansion.collapse_all();
+        assert_eq!(group_rows(&ids, &rows, &row_index_map, &[Field::Team], &expansion),
+                   vec![group(&["b"], &[0, 2, 3], false), group(&["a"], &[1], false)]);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::data_grid::{CellEdit, RowEdit};
+
+const MAX_TRANSACTIONS: usize = 100;
+
+/// Every cell changed by one action, undo and redo send it back through the callback it was committed with
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditTransaction<I, U> {
+    /// A cell edit or a paste
+    Cells(Vec<CellEdit<I, U>>),
+    /// A row saved in row edit mode
+    Row(RowEdit<I, U>)
+}
+
+impl<I: Clone, U: Clone> EditTransaction<I, U> {
+    pub fn edits(&self) -> &[CellEdit<I, U>] {
+        match self {
+            EditTransaction::Cells(edits) => edits,
+            EditTransaction::Row(edit) => &edit.changes
+        }
+    }
+
+    /// The edits that undo this transaction, newest change first
+    fn reverted(&self) -> Self {
+        let revert = |edits: &[CellEdit<I, U>]| edits.iter().rev().map(|edit| CellEdit {
+            row_id: edit.row_id.clone(),
+            column: edit.column.clone(),
+            old_value: edit.new_value.clone(),
+            new_value: edit.old_value.clone()
+        }).collect();
+        match self {
+            EditTransaction::Cells(edits) => EditTransaction::Cells(revert(edits)),
+            EditTransaction::Row(edit) => EditTransaction::Row(RowEdit { row_id: edit.row_id.clone(), changes: revert(&edit.changes) })
+        }
+    }
+}
+
+/// Undo and redo stacks of committed edits
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditHistory<I, U> {
+    undo_stack: Vec<EditTransaction<I, U>>,
+    redo_stack: Vec<EditTransaction<I, U>>
+}
+
+impl<I, U> Default for EditHistory<I, U> {
+    fn default() -> Self {
+        Self { undo_stack: Vec::new(), redo_stack: Vec::new() }
+    }
+}
+
+impl<I: Clone, U: Clone> EditHistory<I, U> {
+    /// Records the cells changed by a cell edit or a paste, anything that was undone can no longer be redone
+    pub fn record(&mut self, edits: Vec<CellEdit<I, U>>) {
+        self.push(EditTransaction::Cells(edits));
+    }
+
+    /// Records a row saved in row edit mode, its undo and redo are sent back as row edits
+    pub fn record_row(&mut self, edit: RowEdit<I, U>) {
+        self.push(EditTransaction::Row(edit));
+    }
+
+    fn push(&mut self, transaction: EditTransaction<I, U>) {
+        if transaction.edits().is_empty() {
+            return;
+        }
+        self.redo_stack.clear();
+        self.undo_stack.push(transaction);
+        if self.undo_stack.len() > MAX_TRANSACTIONS {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// Edits that revert the last transaction, newest change first
+    pub fn undo(&mut self) -> Option<EditTransaction<I, U>> {
+        let transaction = self.undo_stack.pop()?;
+        let reverted = transaction.reverted();
+        self.redo_stack.push(transaction);
+        Some(reverted)
+    }
+
+    /// Edits that re-apply the last undone transaction
+    pub fn redo(&mut self) -> Option<EditTransaction<I, U>> {
+        let transaction = self.redo_stack.pop()?;
+        self.undo_stack.push(transaction.clone());
+        Some(transaction)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(row_id: usize, old_value: &str, new_value: &str) -> CellEdit<usize, u8> {
+        CellEdit { row_id, column: 0, old_value: old_value.to_string(), new_value: new_value.to_string() }
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history = EditHistory::default();
+        assert_eq!(history.undo(), None);
+        history.record(vec![edit(1, "a", "b")]);
+        history.record(vec![edit(1, "b", "c"), edit(2, "x", "y")]);
+
+        assert_eq!(history.undo(), Some(EditTransaction::Cells(vec![edit(2, "y", "x"), edit(1, "c", "b")])));
+        assert_eq!(history.undo(), Some(EditTransaction::Cells(vec![edit(1, "b", "a")])));
+        assert!(!history.can_undo());
+        assert_eq!(history.redo(), Some(EditTransaction::Cells(vec![edit(1, "a", "b")])));
+        assert!(history.can_redo());
+
+        // a new edit discards the redo stack
+        history.record(vec![edit(3, "m", "n")]);
+        assert!(!history.can_redo());
+        assert_eq!(history.undo(), Some(EditTransaction::Cells(vec![edit(3, "n", "m")])));
+    }
+
+    #[test]
+    fn test_row_edits_are_replayed_as_row_edits() {
+        let mut history = EditHistory::default();
+        history.record_row(RowEdit { row_id: 1, changes: vec![edit(1, "a", "b"), edit(1, "x", "y")] });
+        let reverted = RowEdit { row_id: 1, changes: vec![edit(1, "y", "x"), edit(1, "b", "a")] };
+        assert_eq!(history.undo(), Some(EditTransaction::Row(reverted)));
+        assert_eq!(history.redo(), Some(EditTransaction::Row(RowEdit { row_id: 1, changes: vec![edit(1, "a", "b"), edit(1, "x", "y")] })));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = EditHistory::default();
+        for i in 0..MAX_TRANSACTIONS + 5 {
+            history.record(vec![edit(i, "a", "b")]);
+        }
+        history.record(vec![]);
+        let mut undone = 0;
+        while history.undo().is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_TRANSACTIONS);
+    }
+}
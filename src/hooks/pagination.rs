@@ -19,18 +19,39 @@ impl Pagination {
             total_rows
         }
     }
+
+    /// Clamps a page number to the pages available, an empty grid still has page 1
+    pub fn clamp_page(&self, page: i32) -> i32 {
+        page.min(self.number_pages).max(1)
+    }
+
+    pub fn with_page(mut self, page: i32) -> Self {
+        self.page = self.clamp_page(page);
+        self
+    }
+
+    /// Page holding the row at `index`, every row is on page 1 when the page size is not positive
+    pub fn page_of(&self, index: usize) -> i32 {
+        if self.page_size > 0 {
+            index as i32 / self.page_size + 1
+        } else {
+            1
+        }
+    }
 }
 
 #[hook]
-pub fn use_page_view(p: Pagination, data_indexes: &Vec<String>) -> Rc<Vec<String>> {
-    let slice = use_memo(|p| get_page_view(p, data_indexes), p);
+pub fn use_page_view<I>(p: Pagination, data_indexes: Rc<Vec<I>>) -> Rc<Vec<I>>
+    where I: Clone + PartialEq + 'static
+{
+    let slice = use_memo(|(p, data_indexes)| get_page_view(p, data_indexes), (p, data_indexes));
     slice
 }
 
-fn get_page_view(p: &Pagination, data_indexes: &Vec<String>) -> Vec<String> {
+fn get_page_view<I: Clone>(p: &Pagination, data_indexes: &[I]) -> Vec<I> {
     let start = (p.page - 1) * p.page_size;
     let end = start + p.page_size;
-    return if end >= data_indexes.len() as i32 {
+    if end >= data_indexes.len() as i32 {
         data_indexes[start as usize..].to_vec()
     } else {
         data_indexes[start as usize..end as usize].to_vec()
@@ -52,12 +73,35 @@ mod tests {
         assert_eq!(p.page_size, 10);
     }
 
+    #[test]
+    fn test_clamp_page() {
+        let p = Pagination::new(50000, 10);
+        assert_eq!(p.clamp_page(0), 1);
+        assert_eq!(p.clamp_page(-3), 1);
+        assert_eq!(p.clamp_page(42), 42);
+        assert_eq!(p.clamp_page(5001), 5000);
+        assert_eq!(Pagination::new(0, 10).clamp_page(3), 1);
+        assert_eq!(p.with_page(7).page, 7);
+        assert_eq!(p.with_page(0).page, 1);
+        assert_eq!(p.with_page(5001).page, 5000);
+    }
+
+    #[test]
+    fn test_page_of() {
+        let p = Pagination::new(105, 10);
+        assert_eq!(p.page_of(0), 1);
+        assert_eq!(p.page_of(9), 1);
+        assert_eq!(p.page_of(10), 2);
+        assert_eq!(p.page_of(104), 11);
+        assert_eq!(Pagination::new(105, 0).page_of(104), 1);
+    }
+
     #[test]
     fn test_get_page_view() {
         let total_rows = 105;
         let page_size = 10;
         let p = Pagination::new(total_rows, page_size);
-        let data_indexes = (0..total_rows).map(|i| i.to_string()).collect();
+        let data_indexes: Vec<String> = (0..total_rows).map(|i| i.to_string()).collect();
         let page_view = get_page_view(&p, &data_indexes);
         assert_eq!(page_view, vec!["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"]);
     }
@@ -0,0 +1,194 @@
+use crate::data_grid::GridDataColumn;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvImportOptions {
+    pub delimiter: char
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self { delimiter: ',' }
+    }
+}
+
+/// Splits CSV text into records (RFC 4180). Quoted fields may hold delimiters, quotes and line breaks,
+/// a leading byte order mark is skipped and blank lines are dropped.
+pub fn parse_csv(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    // a field that was quoted is kept even when empty, so `""` on its own line is a record
+    let mut field_started = false;
+    let mut chars = text.strip_prefix('\u{feff}').unwrap_or(text).chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                c => field.push(c)
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => {
+                quoted = true;
+                field_started = true;
+            }
+            c if c == delimiter => {
+                record.push(std::mem::take(&mut field));
+                field_started = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                if field_started || !field.is_empty() {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                field_started = false;
+            }
+            c => field.push(c)
+        }
+    }
+    if field_started || !field.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// A record of an imported file with its values under the grid columns they were mapped to
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedRow<U> {
+    /// Position of the record in the file, the header is record 1
+    pub record: usize,
+    pub values: Vec<(U, String)>,
+    /// Values rejected by `GridDataColumn::validate_import`, with the message
+    pub errors: Vec<(U, String)>
+}
+
+impl<U: PartialEq> ImportedRow<U> {
+    pub fn value(&self, column: &U) -> Option<&str> {
+        self.values.iter().find(|(c, _)| c == column).map(|(_, value)| value.as_str())
+    }
+
+    pub fn error(&self, column: &U) -> Option<&str> {
+        self.errors.iter().find(|(c, _)| c == column).map(|(_, error)| error.as_str())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// A parsed file whose first record names its columns, with the mapping of those columns onto the grid's
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvImport<U> {
+    pub headers: Vec<String>,
+    /// Grid column each file column is imported into, `None` skips the file column
+    pub mapping: Vec<Option<U>>,
+    records: Vec<Vec<String>>
+}
+
+impl<U: GridDataColumn + Clone + PartialEq> CsvImport<U> {
+    /// Parses the file and maps its columns onto `columns` by header name or field key, ignoring case
+    pub fn parse(text: &str, options: &CsvImportOptions, columns: &[U]) -> Self {
+        let mut records = parse_csv(text, options.delimiter).into_iter();
+        let headers = records.next().unwrap_or_default();
+        let mut mapping: Vec<Option<U>> = Vec::with_capacity(headers.len());
+        for header in &headers {
+            let header = header.trim();
+            let column = columns.iter()
+                .find(|column| !mapping.contains(&Some((*column).clone()))
+                    && (column.get_config().header_name.trim().eq_ignore_ascii_case(header)
+                        || column.get_field_key().eq_ignore_ascii_case(header)))
+                .cloned();
+            mapping.push(column);
+        }
+        Self { headers, mapping, records: records.collect() }
+    }
+
+    /// Maps a file column onto a grid column, a grid column only takes the values of one file column
+    pub fn set_mapping(&mut self, index: usize, column: Option<U>) {
+        if column.is_some() {
+            for mapped in self.mapping.iter_mut().filter(|mapped| **mapped == column) {
+                *mapped = None;
+            }
+        }
+        if let Some(mapped) = self.mapping.get_mut(index) {
+            *mapped = column;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Values of the mapped columns of every record, checked with `GridDataColumn::validate_import`.
+    /// Fields missing at the end of short records are imported as empty values.
+    pub fn rows(&self) -> Vec<ImportedRow<U>> {
+        self.records.iter().enumerate().map(|(i, record)| {
+            let mut row = ImportedRow { record: i + 2, values: Vec::new(), errors: Vec::new() };
+            for (index, column) in self.mapping.iter().enumerate() {
+                let Some(column) = column else { continue };
+                let value = record.get(index).cloned().unwrap_or_default();
+                if let Err(error) = column.validate_import(&value) {
+                    row.errors.push((column.clone(), error));
+                }
+                row.values.push((column.clone(), value));
+            }
+            row
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Field;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "\u{feff}a,\"b, \"\"c\"\"\"\r\n\r\n\"multi\nline\",\r\n\"\"\nlast";
+        assert_eq!(parse_csv(text, ','), vec![
+            strings(&["a", "b, \"c\""]),
+            strings(&["multi\nline", ""]),
+            strings(&[""]),
+            strings(&["last"]),
+        ]);
+        assert_eq!(parse_csv("a;b\n", ';'), vec![strings(&["a", "b"])]);
+        assert!(parse_csv("", ',').is_empty());
+    }
+
+    #[test]
+    fn test_csv_import_mapping_and_validation() {
+        let text = "AGE,Notes,name\n42,x,Ada\nold,y\n";
+        let mut import = CsvImport::parse(text, &CsvImportOptions::default(), &[Field::Name, Field::Age]);
+        assert_eq!(import.mapping, vec![Some(Field::Age), None, Some(Field::Name)]);
+        // field keys match as well as header names
+        assert_eq!(CsvImport::parse("NOTE\n", &CsvImportOptions::default(), &[Field::Note]).mapping, vec![Some(Field::Note)]);
+
+        let rows = import.rows();
+        assert_eq!(rows[0].value(&Field::Name), Some("Ada"));
+        assert!(rows[0].is_valid());
+        assert_eq!(rows[1].record, 3);
+        assert_eq!(rows[1].value(&Field::Name), Some(""));
+        assert_eq!(rows[1].error(&Field::Age), Some("not a number"));
+
+        // moving a grid column to another file column unmaps it from the first
+        import.set_mapping(1, Some(Field::Name));
+        assert_eq!(import.mapping, vec![Some(Field::Age), Some(Field::Name), None]);
+        assert_eq!(import.rows()[0].value(&Field::Name), Some("x"));
+    }
+}
@@ -1,7 +1,33 @@
 pub mod data_grid;
+pub mod aggregation;
+pub mod clipboard;
+pub mod columns;
+pub mod editors;
+pub mod error;
+pub mod export;
+pub mod filtering;
+pub mod grouping;
+pub mod import;
+pub mod history;
+pub mod row_editing;
+pub mod row_height;
+pub mod row_state;
+pub mod sorting;
+pub mod stylesheet;
+pub mod styling;
+pub mod theme;
+pub mod tree;
+pub mod grid_api;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
 mod grid_row;
 mod grid_cell;
+pub mod grid_cell_editor;
+pub mod grid_csv_import;
+pub mod grid_header_menu;
 pub mod grid_pagination_bar;
+#[cfg(test)]
+mod test_support;
 pub mod hooks {
     pub mod pagination;
 }
@@ -0,0 +1,95 @@
+use crate::data_grid::{CellEdit, GridDataColumn};
+
+/// A row in row edit mode, only values that differ from the row are kept as drafts
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowEditing<I, U> {
+    pub row_id: I,
+    /// Column that was double clicked, its editor gets focus
+    pub focus: U,
+    pub errors: Vec<(U, String)>,
+    pub pending: bool,
+    drafts: Vec<(U, String)>
+}
+
+impl<I: Clone, U: Copy + PartialEq> RowEditing<I, U> {
+    pub fn new(row_id: I, focus: U) -> Self {
+        Self { row_id, focus, errors: Vec::new(), pending: false, drafts: Vec::new() }
+    }
+
+    /// Records an editor's value, setting it back to the original value clears the draft
+    pub fn set_value(&mut self, column: U, original: &str, value: String) {
+        self.drafts.retain(|(c, _)| *c != column);
+        self.errors.retain(|(c, _)| *c != column);
+        if value != original {
+            self.drafts.push((column, value));
+        }
+    }
+
+    pub fn draft(&self, column: &U) -> Option<&str> {
+        self.drafts.iter().find(|(c, _)| c == column).map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_dirty(&self, column: &U) -> bool {
+        self.draft(column).is_some()
+    }
+
+    pub fn error(&self, column: &U) -> Option<&str> {
+        self.errors.iter().find(|(c, _)| c == column).map(|(_, e)| e.as_str())
+    }
+
+    /// Changed cells in the order they were edited
+    pub fn changes<T>(&self, row: &T) -> Vec<CellEdit<I, U>>
+        where U: GridDataColumn<RowType=T>
+    {
+        self.drafts.iter().map(|(column, value)| CellEdit {
+            row_id: self.row_id.clone(),
+            column: *column,
+            old_value: column.get_value(row),
+            new_value: value.clone()
+        }).collect()
+    }
+}
+
+/// Runs each changed column's `validate`, returning the columns that failed
+pub fn validate_changes<I, T, U>(row: &T, changes: &[CellEdit<I, U>]) -> Vec<(U, String)>
+    where U: GridDataColumn<RowType=T> + Copy
+{
+    changes.iter()
+        .filter_map(|edit| edit.column.validate(row, &edit.new_value).err().map(|error| (edit.column, error)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{person, Field};
+
+    #[test]
+    fn test_only_changed_columns_are_dirty() {
+        let row = person(1, "Ada").age("36");
+        let mut editing = RowEditing::new(1, Field::Name);
+        editing.set_value(Field::Name, "Ada", "Ada".to_string());
+        assert!(!editing.is_dirty(&Field::Name));
+        editing.set_value(Field::Age, "36", "37".to_string());
+        editing.set_value(Field::Name, "Ada", "Grace".to_string());
+        editing.set_value(Field::Name, "Ada", "Ada".to_string());
+        assert!(!editing.is_dirty(&Field::Name));
+        let changes = editing.changes(&row);
+        assert_eq!(changes, vec![CellEdit { row_id: 1, column: Field::Age, old_value: "36".to_string(), new_value: "37".to_string() }]);
+    }
+
+    #[test]
+    fn test_validate_changes() {
+        let row = person(1, "Ada").age("36");
+        let mut editing = RowEditing::new(1, Field::Name);
+        editing.set_value(Field::Age, "36", "old".to_string());
+        editing.set_value(Field::Name, "Ada", "Grace".to_string());
+        let errors = validate_changes(&row, &editing.changes(&row));
+        assert_eq!(errors, vec![(Field::Age, "not a number".to_string())]);
+        editing.errors = errors;
+        assert_eq!(editing.error(&Field::Age), Some("not a number"));
+        // editing the value again clears its error
+        editing.set_value(Field::Age, "36", "40".to_string());
+        assert_eq!(editing.error(&Field::Age), None);
+    }
+}
@@ -0,0 +1,93 @@
+/// Preset row heights, `Props::row_height` overrides the preset
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Density {
+    Compact,
+    #[default]
+    Standard,
+    Comfortable
+}
+
+impl Density {
+    pub fn row_height(&self) -> i32 {
+        match self {
+            Density::Compact => 36,
+            Density::Standard => 52,
+            Density::Comfortable => 68
+        }
+    }
+}
+
+/// Top offsets of rows with varying heights, for mapping between rows and scroll positions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowHeights {
+    /// `offsets[i]` is the top of row `i`, the last entry is the total height
+    offsets: Vec<i32>
+}
+
+impl Default for RowHeights {
+    fn default() -> Self {
+        Self { offsets: vec![0] }
+    }
+}
+
+impl RowHeights {
+    pub fn new(heights: impl IntoIterator<Item=i32>) -> Self {
+        let mut offsets = vec![0];
+        let mut top = 0;
+        for height in heights {
+            top += height;
+            offsets.push(top);
+        }
+        Self { offsets }
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn offset(&self, index: usize) -> Option<i32> {
+        (index < self.len()).then(|| self.offsets[index])
+    }
+
+    pub fn height(&self, index: usize) -> Option<i32> {
+        (index < self.len()).then(|| self.offsets[index + 1] - self.offsets[index])
+    }
+
+    pub fn total_height(&self) -> i32 {
+        self.offsets[self.len()]
+    }
+
+    /// Row covering the given distance from the top, the last row for offsets past the end
+    pub fn index_at(&self, offset: i32) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let index = self.offsets.partition_point(|top| *top <= offset).saturating_sub(1);
+        Some(index.min(self.len() - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_offsets() {
+        let heights = RowHeights::new([36, 52, 100]);
+        assert_eq!(heights.len(), 3);
+        assert_eq!(heights.offset(2), Some(88));
+        assert_eq!(heights.offset(3), None);
+        assert_eq!(heights.height(2), Some(100));
+        assert_eq!(heights.total_height(), 188);
+        assert_eq!(heights.index_at(0), Some(0));
+        assert_eq!(heights.index_at(35), Some(0));
+        assert_eq!(heights.index_at(36), Some(1));
+        assert_eq!(heights.index_at(500), Some(2));
+        assert_eq!(RowHeights::new([]).index_at(0), None);
+        assert_eq!(RowHeights::default().len(), 0);
+    }
+}
@@ -0,0 +1,464 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::Deref;
+use std::rc::Rc;
+use yew::html::{ImplicitClone, IntoPropValue};
+use crate::data_grid::GridData;
+use crate::error::GridError;
+
+/// How the grid treats rows whose id is already used by an earlier row
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum DuplicateIdPolicy {
+    /// Render no rows and show the error over the grid until the ids are fixed
+    #[default]
+    Reject,
+    /// Ignore every row after the first with the same id
+    KeepFirst,
+    /// Ignore every row before the last with the same id
+    KeepLast,
+    /// Give later rows the id from `GridData::suffixed_id`, edits to them are reported with that id.
+    /// Rows that cannot be given a unique id are left out and reported with `GridError::UnresolvedRowIds`.
+    AutoSuffix
+}
+
+/// The grid's `rows` prop, shared with the grid's callbacks so they can read the rows without copying them.
+/// A `Vec<T>` converts into it, `rows={rows}` works as before.
+#[derive(Debug)]
+pub struct Rows<T>(Rc<Vec<T>>);
+
+impl<T> Clone for Rows<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> ImplicitClone for Rows<T> {}
+
+impl<T> Default for Rows<T> {
+    fn default() -> Self {
+        Self(Rc::new(Vec::new()))
+    }
+}
+
+impl<T> Rows<T> {
+    /// Whether both are the same rows rather than equal ones
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Rows<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.0 == other.0
+    }
+}
+
+impl<T> Deref for Rows<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> From<Vec<T>> for Rows<T> {
+    fn from(rows: Vec<T>) -> Self {
+        Self(Rc::new(rows))
+    }
+}
+
+impl<T> From<Rc<Vec<T>>> for Rows<T> {
+    fn from(rows: Rc<Vec<T>>) -> Self {
+        Self(rows)
+    }
+}
+
+impl<T> IntoPropValue<Rows<T>> for Vec<T> {
+    fn into_prop_value(self) -> Rows<T> {
+        self.into()
+    }
+}
+
+impl<T> IntoPropValue<Rows<T>> for Rc<Vec<T>> {
+    fn into_prop_value(self) -> Rows<T> {
+        self.into()
+    }
+}
+
+/// Index of the `rows` prop by row id, kept in sync with the prop across renders
+#[derive(Debug)]
+pub struct RowState<I> {
+    pub row_index_map: HashMap<I, usize>,
+    /// Row ids in the order of the `rows` prop, sorting is applied on top of this
+    pub sort_order: Vec<I>,
+    /// Duplicate ids found in the current `rows`, handled according to the policy
+    pub error: Option<GridError>,
+    source_ids: Vec<I>,
+    policy: DuplicateIdPolicy
+}
+
+impl<I> Default for RowState<I> {
+    fn default() -> Self {
+        Self {
+            row_index_map: HashMap::new(),
+            sort_order: Vec::new(),
+            error: None,
+            source_ids: Vec::new(),
+            policy: DuplicateIdPolicy::default()
+        }
+    }
+}
+
+/// What changed between two versions of the `rows` prop, keyed by row id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowDiff<I> {
+    pub added: Vec<I>,
+    pub removed: Vec<I>,
+    /// Rows that exist in both versions changed position
+    pub moved: bool
+}
+
+impl<I: Hash + Eq + Clone + Debug> RowState<I> {
+    pub fn from_rows<T: GridData<IdType=I>>(rows: &[T], policy: DuplicateIdPolicy) -> Self {
+        let mut state = RowState { policy, ..Default::default() };
+        state.sync(rows, policy);
+        state
+    }
+
+    /// Brings the index up to date with `rows`, reading every row's id and rebuilding the index when they
+    /// changed. Returns `None` when the ids are unchanged, which is the case for in place value updates.
+    pub fn sync<T: GridData<IdType=I>>(&mut self, rows: &[T], policy: DuplicateIdPolicy) -> Option<RowDiff<I>> {
+        let source_ids: Vec<I> = rows.iter().map(|row| row.get_id()).collect();
+        if source_ids == self.source_ids && policy == self.policy {
+            return None;
+        }
+        let (ids, error) = resolve_ids::<T>(&source_ids, policy);
+        self.source_ids = source_ids;
+        self.policy = policy;
+        self.error = error;
+        let row_index_map: HashMap<I, usize> = ids.iter()
+            .filter_map(|(i, id)| Some((id.clone()?, *i)))
+            .collect();
+        let ids: Vec<I> = ids.into_iter().filter_map(|(_, id)| id).collect();
+        if ids == self.sort_order {
+            // a policy can keep the same ids while pointing them at other rows
+            self.row_index_map = row_index_map;
+            return None;
+        }
+        let added: Vec<I> = ids.iter().filter(|id| !self.row_index_map.contains_key(*id)).cloned().collect();
+        let removed: Vec<I> = self.sort_order.iter().filter(|id| !row_index_map.contains_key(*id)).cloned().collect();
+        let removed_set: HashSet<&I> = removed.iter().collect();
+        let added_set: HashSet<&I> = added.iter().collect();
+        let kept_before = self.sort_order.iter().filter(|id| !removed_set.contains(id));
+        let kept_after = ids.iter().filter(|id| !added_set.contains(id));
+        let moved = !kept_before.eq(kept_after);
+        self.row_index_map = row_index_map;
+        self.sort_order = ids;
+        Some(RowDiff { added, removed, moved })
+    }
+
+    pub fn policy(&self) -> DuplicateIdPolicy {
+        self.policy
+    }
+
+    /// Like `sync`, but patches the index with the ids of `transactions`, the transactions applied to the rows
+    /// since the index was last brought up to date, without reading the rows. Falls back to `sync` when they
+    /// don't account for every row in `rows`, could add a duplicate id or the policy changed.
+    pub fn sync_transactions<T: GridData<IdType=I>>(&mut self,
+                                                    rows: &[T],
+                                                    policy: DuplicateIdPolicy,
+                                                    transactions: &[RowDiff<I>]) -> Option<RowDiff<I>> {
+        if transactions.is_empty() || policy != self.policy || !self.patch(transactions, rows.len()) {
+            return self.sync(rows, policy);
+        }
+        let added: Vec<I> = transactions.iter().flat_map(|diff| diff.added.iter().cloned()).collect();
+        let removed: Vec<I> = transactions.iter().flat_map(|diff| diff.removed.iter().cloned()).collect();
+        (!added.is_empty() || !removed.is_empty()).then_some(RowDiff { added, removed, moved: false })
+    }
+
+    /// Removes the removed ids and appends the added ones in the order `RowTransaction::apply` does, re-indexing
+    /// only the rows after the first removed one. Returns false without changing anything when the ids can't
+    /// be patched in.
+    fn patch(&mut self, transactions: &[RowDiff<I>], row_count: usize) -> bool {
+        let removed: HashSet<&I> = transactions.iter().flat_map(|diff| &diff.removed).collect();
+        let mut added: HashSet<&I> = HashSet::new();
+        let removed_count: usize = transactions.iter().map(|diff| diff.removed.len()).sum();
+        let valid = self.error.is_none()
+            && removed.len() == removed_count
+            && removed.iter().all(|id| self.row_index_map.contains_key(*id))
+            && transactions.iter().flat_map(|diff| &diff.added)
+                .all(|id| added.insert(id) && !removed.contains(id) && !self.row_index_map.contains_key(id))
+            && self.source_ids.len() - removed.len() + added.len() == row_count;
+        if !valid {
+            return false;
+        }
+        if let Some(first) = removed.iter().map(|id| self.row_index_map[*id]).min() {
+            self.row_index_map.retain(|id, _| !removed.contains(id));
+            self.source_ids.retain(|id| !removed.contains(id));
+            for (i, id) in self.source_ids.iter().enumerate().skip(first) {
+                self.row_index_map.insert(id.clone(), i);
+            }
+        }
+        for id in transactions.iter().flat_map(|diff| &diff.added) {
+            self.row_index_map.insert(id.clone(), self.source_ids.len());
+            self.source_ids.push(id.clone());
+        }
+        // without duplicate ids every row is indexed by its own id
+        self.sort_order = self.source_ids.clone();
+        true
+    }
+}
+
+/// Position in `rows` and the id the row is indexed by, `None` for rows left out by the policy
+type ResolvedIds<I> = Vec<(usize, Option<I>)>;
+
+/// Suffixes tried for a row before it is left out, `GridData::suffixed_id` may keep returning ids that are taken
+const MAX_SUFFIX_ATTEMPTS: usize = 100;
+
+fn resolve_ids<T: GridData>(source_ids: &[T::IdType], policy: DuplicateIdPolicy) -> (ResolvedIds<T::IdType>, Option<GridError>) {
+    let mut counts: HashMap<&T::IdType, usize> = HashMap::new();
+    let mut duplicates = Vec::new();
+    for id in source_ids {
+        let count = counts.entry(id).or_default();
+        *count += 1;
+        if *count == 2 {
+            duplicates.push(format!("{id:?}"));
+        }
+    }
+    if duplicates.is_empty() {
+        return (source_ids.iter().cloned().map(Some).enumerate().collect(), None);
+    }
+    let mut error = Some(GridError::DuplicateRowIds(duplicates));
+    let mut seen: HashSet<&T::IdType> = HashSet::new();
+    let ids = match policy {
+        DuplicateIdPolicy::Reject => Vec::new(),
+        DuplicateIdPolicy::KeepFirst => source_ids.iter()
+            .map(|id| seen.insert(id).then(|| id.clone()))
+            .enumerate()
+            .collect(),
+        DuplicateIdPolicy::KeepLast => {
+            let mut ids: ResolvedIds<T::IdType> = source_ids.iter().enumerate().rev()
+                .map(|(i, id)| (i, seen.insert(id).then(|| id.clone())))
+                .collect();
+            ids.reverse();
+            ids
+        },
+        DuplicateIdPolicy::AutoSuffix => {
+            let mut taken: HashSet<T::IdType> = HashSet::new();
+            // each duplicate continues with the suffix after the one the previous duplicate got
+            let mut next_suffix: HashMap<&T::IdType, usize> = HashMap::new();
+            let mut unresolved: Vec<String> = Vec::new();
+            let ids = source_ids.iter().map(|id| {
+                if taken.insert(id.clone()) {
+                    return Some(id.clone());
+                }
+                let first = *next_suffix.get(id).unwrap_or(&2);
+                // never use a suffixed id that a later row has as its own id
+                let unique = (first..first + MAX_SUFFIX_ATTEMPTS)
+                    .map_while(|n| Some((n, T::suffixed_id(id, n)?)))
+                    .find(|(_, candidate)| !counts.contains_key(candidate) && !taken.contains(candidate));
+                match unique {
+                    Some((n, unique)) => {
+                        next_suffix.insert(id, n + 1);
+                        taken.insert(unique.clone());
+                        Some(unique)
+                    },
+                    None => {
+                        let id = format!("{id:?}");
+                        if !unresolved.contains(&id) {
+                            unresolved.push(id);
+                        }
+                        None
+                    }
+                }
+            }).enumerate().collect();
+            if !unresolved.is_empty() {
+                error = Some(GridError::UnresolvedRowIds(unresolved));
+            }
+            ids
+        }
+    };
+    (ids, error)
+}
+
+/// A batch of row changes applied to the parent's rows in one pass, so a burst of updates costs a single
+/// re-render. Apply it with `GridApi::apply_transaction` to have the grid patch its row index from the
+/// transaction's ids rather than read the id of every row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowTransaction<T: GridData> {
+    pub add: Vec<T>,
+    /// Rows replacing the existing row with the same id, unknown ids are ignored
+    pub update: Vec<T>,
+    pub remove: Vec<T::IdType>
+}
+
+impl<T: GridData> Default for RowTransaction<T> {
+    fn default() -> Self {
+        Self { add: Vec::new(), update: Vec::new(), remove: Vec::new() }
+    }
+}
+
+impl<T: GridData> RowTransaction<T> {
+    pub fn add_row(mut self, row: T) -> Self {
+        self.add.push(row);
+        self
+    }
+
+    pub fn update_row(mut self, row: T) -> Self {
+        self.update.push(row);
+        self
+    }
+
+    pub fn remove_row(mut self, id: T::IdType) -> Self {
+        self.remove.push(id);
+        self
+    }
+
+    /// Applies updates in place and removals in a single pass, then appends the added rows. Returns the ids
+    /// of the added rows and of the rows that were removed.
+    pub fn apply(self, rows: &mut Vec<T>) -> RowDiff<T::IdType> {
+        let mut removed = Vec::new();
+        if !self.update.is_empty() || !self.remove.is_empty() {
+            let mut update: HashMap<T::IdType, T> = self.update.into_iter().map(|row| (row.get_id(), row)).collect();
+            let remove: HashSet<T::IdType> = self.remove.into_iter().collect();
+            rows.retain_mut(|row| {
+                let id = row.get_id();
+                if remove.contains(&id) {
+                    removed.push(id);
+                    return false;
+                }
+                if let Some(new_row) = update.remove(&id) {
+                    *row = new_row;
+                }
+                true
+            });
+        }
+        let added = self.add.iter().map(|row| row.get_id()).collect();
+        rows.extend(self.add);
+        RowDiff { added, removed, moved: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{person, Person};
+
+    fn errors(values: &[&str]) -> Option<GridError> {
+        Some(GridError::DuplicateRowIds(values.iter().map(|v| v.to_string()).collect()))
+    }
+
+    const REJECT: DuplicateIdPolicy = DuplicateIdPolicy::Reject;
+
+    fn duplicate_rows() -> [Person; 5] {
+        [person(1, "a"), person(2, "b"), person(1, "c"), person(3, "d"), person(1, "e")]
+    }
+
+    #[test]
+    fn test_sync_detects_changes() {
+        let mut state = RowState::from_rows(&[person(1, "a"), person(2, "b"), person(3, "c")], REJECT);
+        // value changes keep the ids
+        assert_eq!(state.sync(&[person(1, "x"), person(2, "b"), person(3, "c")], REJECT), None);
+
+        // same length, one row replaced
+        let diff = state.sync(&[person(1, "a"), person(4, "d"), person(3, "c")], REJECT).unwrap();
+        assert_eq!(diff, RowDiff { added: vec![4], removed: vec![2], moved: false });
+        assert_eq!(state.row_index_map[&3], 2);
+
+        let diff = state.sync(&[person(3, "c"), person(1, "a")], REJECT).unwrap();
+        assert_eq!(diff, RowDiff { added: vec![], removed: vec![4], moved: true });
+        assert_eq!(state.sort_order, vec![3, 1]);
+        assert_eq!(state.row_index_map[&1], 1);
+    }
+
+    #[test]
+    fn test_reject_duplicate_ids() {
+        let mut state = RowState::from_rows(&[person(1, "a")], REJECT);
+        let diff = state.sync(&duplicate_rows(), REJECT).unwrap();
+        assert_eq!(diff.removed, vec![1]);
+        assert!(state.sort_order.is_empty());
+        assert_eq!(state.error, errors(&["1"]));
+
+        // fixing the ids clears the error
+        state.sync(&[person(1, "a"), person(2, "b")], REJECT);
+        assert_eq!(state.sort_order, vec![1, 2]);
+        assert_eq!(state.error, None);
+    }
+
+    #[test]
+    fn test_keep_first_duplicate() {
+        let state = RowState::from_rows(&duplicate_rows(), DuplicateIdPolicy::KeepFirst);
+        assert_eq!(state.sort_order, vec![1, 2, 3]);
+        assert_eq!(state.row_index_map[&1], 0);
+        assert_eq!(state.error, errors(&["1"]));
+    }
+
+    #[test]
+    fn test_keep_last_duplicate() {
+        let state = RowState::from_rows(&duplicate_rows(), DuplicateIdPolicy::KeepLast);
+        assert_eq!(state.sort_order, vec![2, 3, 1]);
+        assert_eq!(state.row_index_map[&1], 4);
+        assert_eq!(state.error, errors(&["1"]));
+    }
+
+    #[test]
+    fn test_auto_suffix_duplicates() {
+        // 12 belongs to a real row so the suffixes skip it
+        let mut rows = duplicate_rows().to_vec();
+        rows.push(person(12, "f"));
+        let mut state = RowState::from_rows(&rows, DuplicateIdPolicy::AutoSuffix);
+        assert_eq!(state.sort_order, vec![1, 2, 13, 3, 14, 12]);
+        assert_eq!(state.row_index_map[&14], 4);
+        assert_eq!(state.error, errors(&["1"]));
+
+        // the suffixed ids are stable while the rows keep their order
+        assert_eq!(state.sync(&rows, DuplicateIdPolicy::AutoSuffix), None);
+    }
+
+    #[test]
+    fn test_auto_suffix_gives_up() {
+        // every suffixed id tried, 12 to 111, belongs to a real row
+        let mut rows = vec![person(1, "a"), person(1, "b")];
+        rows.extend((12..112).map(|id| person(id, "c")));
+        let state = RowState::from_rows(&rows, DuplicateIdPolicy::AutoSuffix);
+        assert_eq!(state.sort_order.len(), rows.len() - 1);
+        assert!(!state.row_index_map.values().any(|i| *i == 1));
+        assert_eq!(state.error, Some(GridError::UnresolvedRowIds(vec!["1".to_string()])));
+    }
+
+    #[test]
+    fn test_apply_transaction() {
+        let mut rows = vec![person(1, "a"), person(2, "b"), person(3, "c")];
+        let diff = RowTransaction::default()
+            .add_row(person(4, "d"))
+            .update_row(person(3, "C"))
+            .update_row(person(9, "ignored"))
+            .remove_row(1)
+            .remove_row(8)
+            .apply(&mut rows);
+        assert_eq!(rows, vec![person(2, "b"), person(3, "C"), person(4, "d")]);
+        assert_eq!(diff, RowDiff { added: vec![4], removed: vec![1], moved: false });
+    }
+
+    #[test]
+    fn test_sync_transactions_patches_the_index() {
+        let mut rows = vec![person(1, "a"), person(2, "b"), person(3, "c")];
+        let mut state = RowState::from_rows(&rows, REJECT);
+        let first = RowTransaction::default().remove_row(1).add_row(person(4, "d")).apply(&mut rows);
+        let second = RowTransaction::default().update_row(person(2, "B")).remove_row(3).apply(&mut rows);
+        // only the number of rows is checked, the ids come from the transactions
+        let stale: Vec<Person> = (10..12).map(|id| person(id, "x")).collect();
+        let diff = state.sync_transactions(&stale, REJECT, &[first.clone(), second.clone()]);
+        assert_eq!(diff, Some(RowDiff { added: vec![4], removed: vec![1, 3], moved: false }));
+        assert_eq!(state.sort_order, vec![2, 4]);
+        assert_eq!((state.row_index_map[&2], state.row_index_map[&4]), (0, 1));
+        assert_eq!(state.row_index_map.len(), 2);
+
+        // transactions that don't add up to the rows fall back to reading the ids
+        let mut state = RowState::from_rows(&[person(1, "a"), person(2, "b"), person(3, "c")], REJECT);
+        let diff = state.sync_transactions(&rows, REJECT, &[first]).unwrap();
+        assert_eq!((diff.added, diff.removed), (vec![4], vec![1, 3]));
+        assert_eq!(state.sort_order, vec![2, 4]);
+    }
+}
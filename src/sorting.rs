@@ -0,0 +1,113 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::data_grid::GridDataColumn;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SortModel<U> {
+    pub column: U,
+    pub direction: SortDirection
+}
+
+/// Compares cell values numerically when both parse as numbers and as strings when neither does,
+/// numbers come before every other value. A total order, so sorting a column mixing both is safe.
+pub fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        // total_cmp orders NaN and the infinities too
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b)
+    }
+}
+
+/// Stable sort of row ids by the value of the sort column
+pub fn sort_row_ids<I, T, U>(ids: &mut [I],
+                             rows: &[T],
+                             row_index_map: &HashMap<I, usize>,
+                             sort: &SortModel<U>)
+    where I: Hash + Eq + Clone, U: GridDataColumn<RowType=T>
+{
+    // values are looked up once rather than on every comparison
+    let mut keyed: Vec<(String, I)> = ids.iter()
+        .map(|id| (sort.column.get_value(&rows[row_index_map[id]]), id.clone()))
+        .collect();
+    keyed.sort_by(|(a, _), (b, _)| {
+        let ordering = compare_values(a, b);
+        match sort.direction {
+            SortDirection::Asc => ordering,
+            SortDirection::Desc => ordering.reverse()
+        }
+    });
+    for (slot, (_, id)) in ids.iter_mut().zip(keyed) {
+        *slot = id;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{people, Field};
+
+    fn sorted_names(names: &[&str], direction: SortDirection) -> Vec<String> {
+        let rows = people(names);
+        let row_index_map: HashMap<u32, usize> = rows.iter().enumerate().map(|(i, row)| (row.id, i)).collect();
+        let mut ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        sort_row_ids(&mut ids, &rows, &row_index_map, &SortModel { column: Field::Name, direction });
+        ids.iter().map(|id| rows[row_index_map[id]].name.clone()).collect()
+    }
+
+    #[test]
+    fn test_compare_values() {
+        assert_eq!(compare_values("2", "10"), Ordering::Less);
+        assert_eq!(compare_values("1.5", "-3"), Ordering::Greater);
+        assert_eq!(compare_values("apple", "banana"), Ordering::Less);
+        assert_eq!(compare_values("10", "apple"), Ordering::Less);
+        assert_eq!(compare_values("1x", "2"), Ordering::Greater);
+        assert_eq!(compare_values("NaN", "inf"), Ordering::Greater);
+        assert_eq!(compare_values("-inf", "-1e300"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_compare_values_is_a_total_order() {
+        let values = ["2", "10", "1x", "1", " 3 ", "NaN", "nan", "-NaN", "inf", "-inf", "0", "-0", "1.0", "", "apple", "Apple", "10a"];
+        for a in values {
+            assert_eq!(compare_values(a, a), Ordering::Equal);
+            for b in values {
+                assert_eq!(compare_values(a, b), compare_values(b, a).reverse(), "{a:?} {b:?}");
+                for c in values {
+                    if compare_values(a, b) != Ordering::Greater && compare_values(b, c) != Ordering::Greater {
+                        assert_ne!(compare_values(a, c), Ordering::Greater, "{a:?} <= {b:?} <= {c:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_row_ids() {
+        let rows = people(&["10", "2", "33", "2"]);
+        let row_index_map: HashMap<usize, usize> = (0..rows.len()).map(|i| (i, i)).collect();
+        let mut ids: Vec<usize> = (0..rows.len()).collect();
+        sort_row_ids(&mut ids, &rows, &row_index_map, &SortModel { column: Field::Name, direction: SortDirection::Asc });
+        assert_eq!(ids, vec![1, 3, 0, 2]);
+        sort_row_ids(&mut ids, &rows, &row_index_map, &SortModel { column: Field::Name, direction: SortDirection::Desc });
+        assert_eq!(ids, vec![2, 0, 1, 3]);
+    }
+
+    #[test]
+    fn test_sort_row_ids_mixing_numbers_and_text() {
+        assert_eq!(sorted_names(&["1x", "10", "NaN", "b", "2", "inf", "a", "-inf"], SortDirection::Asc),
+                   vec!["-inf", "2", "10", "inf", "NaN", "1x", "a", "b"]);
+        // long mixed columns used to make `sort_by` panic on an inconsistent order
+        let names: Vec<&str> = (0..500).map(|i| ["2", "10", "1x", "NaN", "nan", "inf", "x"][i * 7 % 11 % 7]).collect();
+        let sorted = sorted_names(&names, SortDirection::Desc);
+        assert!(sorted.windows(2).all(|pair| compare_values(&pair[0], &pair[1]) != Ordering::Less));
+    }
+}
@@ -0,0 +1,130 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use web_sys::Element;
+use yew::prelude::*;
+
+/// Put before every class name a grid renders, provided to the grid's components as a context so
+/// grids built from different versions of this crate can share a page
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassPrefix(pub String);
+
+impl ClassPrefix {
+    pub fn class(&self, name: &str) -> String {
+        format!("{}{name}", self.0)
+    }
+}
+
+/// Adds the prefix to the class selectors of the bundled stylesheet, custom properties keep their names
+pub fn prefix_css(css: &str, prefix: &str) -> String {
+    if prefix.is_empty() {
+        return css.to_string();
+    }
+    css.replace(".yew-data-grid-", &format!(".{prefix}yew-data-grid-"))
+        .replace(".yew-grid-", &format!(".{prefix}yew-grid-"))
+}
+
+/// Number of mounted grids using each stylesheet, keyed by class prefix
+#[derive(Debug, Default)]
+struct StyleRegistry {
+    users: HashMap<String, usize>
+}
+
+impl StyleRegistry {
+    /// Counts a user of the stylesheet, true for the first one, which injects it
+    fn acquire(&mut self, key: &str) -> bool {
+        let users = self.users.entry(key.to_string()).or_default();
+        *users += 1;
+        *users == 1
+    }
+
+    /// Drops a user of the stylesheet, true for the last one, which removes it
+    fn release(&mut self, key: &str) -> bool {
+        match self.users.get_mut(key) {
+            Some(users) if *users > 1 => {
+                *users -= 1;
+                false
+            }
+            Some(_) => {
+                self.users.remove(key);
+                true
+            }
+            None => false
+        }
+    }
+}
+
+thread_local! {
+    static REGISTRY: RefCell<StyleRegistry> = RefCell::new(StyleRegistry::default());
+    static STYLE_ELEMENTS: RefCell<HashMap<String, Element>> = RefCell::new(HashMap::new());
+}
+
+/// Keeps a stylesheet in the document head while alive, the last handle for a prefix removes it
+#[derive(Debug)]
+pub struct StylesheetHandle {
+    prefix: String
+}
+
+impl StylesheetHandle {
+    pub fn acquire(css: &str, prefix: &str) -> Self {
+        if REGISTRY.with(|registry| registry.borrow_mut().acquire(prefix)) {
+            if let Some(element) = inject(&prefix_css(css, prefix), prefix) {
+                STYLE_ELEMENTS.with(|elements| elements.borrow_mut().insert(prefix.to_string(), element));
+            }
+        }
+        Self { prefix: prefix.to_string() }
+    }
+}
+
+impl Drop for StylesheetHandle {
+    fn drop(&mut self) {
+        if REGISTRY.with(|registry| registry.borrow_mut().release(&self.prefix)) {
+            if let Some(element) = STYLE_ELEMENTS.with(|elements| elements.borrow_mut().remove(&self.prefix)) {
+                element.remove();
+            }
+        }
+    }
+}
+
+fn inject(css: &str, prefix: &str) -> Option<Element> {
+    let document = web_sys::window()?.document()?;
+    let head = document.head()?;
+    let style = document.create_element("style").ok()?;
+    style.set_attribute("data-yew-data-grid", prefix).ok()?;
+    style.set_text_content(Some(css));
+    head.append_child(&style).ok()?;
+    Some(style)
+}
+
+/// Injects the stylesheet into the document head for as long as the component is mounted,
+/// shared with every other mounted component using the same prefix
+#[hook]
+pub fn use_stylesheet(css: &'static str, prefix: String, enabled: bool) -> Rc<Option<StylesheetHandle>> {
+    // acquired while rendering rather than in an effect, so the first paint is already styled
+    use_memo(|(prefix, enabled)| enabled.then(|| StylesheetHandle::acquire(css, prefix)), (prefix, enabled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_counts_users() {
+        let mut registry = StyleRegistry::default();
+        assert!(registry.acquire(""));
+        assert!(!registry.acquire(""));
+        assert!(registry.acquire("v2-"));
+        assert!(!registry.release(""));
+        assert!(registry.release(""));
+        assert!(!registry.release(""));
+        assert!(registry.release("v2-"));
+    }
+
+    #[test]
+    fn test_prefix_css() {
+        let css = ".yew-data-grid-row:hover { color: var(--yew-data-grid-accent-color); }\n.yew-grid-pagination-bar {}";
+        assert_eq!(prefix_css(css, "v2-"),
+                   ".v2-yew-data-grid-row:hover { color: var(--yew-data-grid-accent-color); }\n.v2-yew-grid-pagination-bar {}");
+        assert_eq!(prefix_css(css, ""), css);
+    }
+}
@@ -0,0 +1,195 @@
+#[cfg(feature = "regex")]
+use std::fmt;
+use crate::data_grid::GridDataColumn;
+
+/// A regular expression for `Condition::Matches`, in the syntax of the `regex` crate. Matches anywhere
+/// in the value unless anchored, and runs in time linear in the value's length.
+#[cfg(feature = "regex")]
+#[derive(Clone)]
+pub struct Pattern(regex::Regex);
+
+#[cfg(feature = "regex")]
+impl Pattern {
+    pub fn new(source: &str) -> Result<Self, String> {
+        regex::Regex::new(source).map(Self).map_err(|e| e.to_string())
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.0.is_match(value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+#[cfg(feature = "regex")]
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+#[cfg(feature = "regex")]
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pattern({:?})", self.as_str())
+    }
+}
+
+/// Test a `StyleRule` runs on its column's value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// The value is a number greater than the threshold
+    Above(f64),
+    /// The value is a number less than the threshold
+    Below(f64),
+    Equals(String),
+    #[cfg(feature = "regex")]
+    Matches(Pattern)
+}
+
+impl Condition {
+    pub fn test(&self, value: &str) -> bool {
+        let number = || value.trim().parse::<f64>().ok();
+        match self {
+            Condition::Above(threshold) => number().is_some_and(|n| n > *threshold),
+            Condition::Below(threshold) => number().is_some_and(|n| n < *threshold),
+            Condition::Equals(expected) => value == expected,
+            #[cfg(feature = "regex")]
+            Condition::Matches(pattern) => pattern.is_match(value)
+        }
+    }
+}
+
+/// Element a `StyleRule` styles when it matches
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum RuleTarget {
+    #[default]
+    Cell,
+    Row
+}
+
+/// Adds a class and style to a cell or its whole row when the column's value meets the condition.
+/// Of the rules matching a cell, or a row, only the one with the highest priority applies,
+/// ties go to the rule listed first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleRule<U> {
+    pub column: U,
+    pub condition: Condition,
+    pub target: RuleTarget,
+    pub class: Option<String>,
+    pub style: Option<String>,
+    pub priority: i32
+}
+
+impl<U> StyleRule<U> {
+    pub fn new(column: U, condition: Condition) -> Self {
+        Self { column, condition, target: RuleTarget::Cell, class: None, style: None, priority: 0 }
+    }
+
+    pub fn class(mut self, class: &str) -> Self {
+        self.class = Some(class.to_string());
+        self
+    }
+
+    pub fn style(mut self, style: &str) -> Self {
+        self.style = Some(style.to_string());
+        self
+    }
+
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Styles the whole row rather than the column's cell
+    pub fn row(mut self) -> Self {
+        self.target = RuleTarget::Row;
+        self
+    }
+}
+
+fn highest_priority<'a, U>(rules: impl DoubleEndedIterator<Item=&'a StyleRule<U>>) -> Option<&'a StyleRule<U>> {
+    // reversed so that `max_by_key`, which keeps the last maximum, picks the first listed rule
+    rules.rev().max_by_key(|rule| rule.priority)
+}
+
+/// The row rule that applies to `row`
+pub fn row_rule<'a, T, U: GridDataColumn<RowType=T>>(rules: &'a [StyleRule<U>], row: &T) -> Option<&'a StyleRule<U>> {
+    highest_priority(rules.iter()
+        .filter(|rule| rule.target == RuleTarget::Row && rule.condition.test(&rule.column.get_value(row))))
+}
+
+/// The cell rule that applies to `column`'s cell of `row`
+pub fn cell_rule<'a, T, U>(rules: &'a [StyleRule<U>], row: &T, column: &U) -> Option<&'a StyleRule<U>>
+    where U: GridDataColumn<RowType=T> + PartialEq
+{
+    let mut matching = rules.iter().filter(|rule| rule.target == RuleTarget::Cell && rule.column == *column).peekable();
+    // the value is only read for columns that have cell rules
+    matching.peek()?;
+    let value = column.get_value(row);
+    highest_priority(matching.filter(|rule| rule.condition.test(&value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{person, Field, Person};
+
+    fn row(age: &str, note: &str) -> Person {
+        person(1, "").age(age).note(note)
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_pattern() {
+        let pattern = |source: &str| Pattern::new(source).unwrap();
+        assert!(pattern("ov.*due").is_match("is overdue"));
+        assert!(!pattern("^due").is_match("overdue"));
+        assert!(pattern("^\\d+$").is_match("2024"));
+        assert!(!pattern("^\\d+$").is_match("20x4"));
+        assert!(pattern("colou?r$|^grey").is_match("grey colour"));
+        assert!(pattern("").is_match("anything"));
+        // groups and counted repeats are supported rather than read as literals
+        assert!(pattern("^(a|b)c$").is_match("ac"));
+        assert!(pattern("^x{2,3}$").is_match("xxx"));
+        assert!(!pattern("^x{2,3}$").is_match("xxxx"));
+        // patterns that backtrack exponentially elsewhere stay fast
+        assert!(!pattern("a*a*a*a*a*a*b").is_match(&"a".repeat(10_000)));
+        assert!(Pattern::new("[a-").is_err());
+        assert!(Pattern::new("*a").is_err());
+        assert!(Pattern::new("(a").is_err());
+        assert_eq!(pattern("a+").as_str(), "a+");
+    }
+
+    #[test]
+    fn test_rules_by_priority() {
+        let rules = vec![
+            StyleRule::new(Field::Age, Condition::Above(100.0)).class("high"),
+            StyleRule::new(Field::Age, Condition::Above(1000.0)).class("very-high").priority(1),
+            StyleRule::new(Field::Age, Condition::Below(0.0)).class("negative"),
+            StyleRule::new(Field::Note, Condition::Equals("overdue".to_string())).class("overdue").row(),
+            StyleRule::new(Field::Note, Condition::Equals("overdue".to_string())).class("late").row(),
+            StyleRule::new(Field::Note, Condition::Equals("due".to_string())).class("due").row(),
+        ];
+        let class = |rule: Option<&StyleRule<Field>>| rule.and_then(|rule| rule.class.clone());
+
+        assert_eq!(class(cell_rule(&rules, &row("500", ""), &Field::Age)).as_deref(), Some("high"));
+        assert_eq!(class(cell_rule(&rules, &row("5000", ""), &Field::Age)).as_deref(), Some("very-high"));
+        assert_eq!(class(cell_rule(&rules, &row("50", ""), &Field::Age)), None);
+        assert_eq!(class(cell_rule(&rules, &row("-1", ""), &Field::Note)), None);
+        // both row rules match with the same priority, the first listed applies
+        assert_eq!(class(row_rule(&rules, &row("0", "overdue"))).as_deref(), Some("overdue"));
+        assert_eq!(class(row_rule(&rules, &row("0", "due"))).as_deref(), Some("due"));
+        assert_eq!(class(row_rule(&rules, &row("0", "done"))), None);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_matches_rule() {
+        let rules = vec![StyleRule::new(Field::Note, Condition::Matches(Pattern::new("due$").unwrap())).class("due").row()];
+        assert!(row_rule(&rules, &row("0", "overdue")).is_some());
+        assert!(row_rule(&rules, &row("0", "due soon")).is_none());
+    }
+}
@@ -0,0 +1,134 @@
+use yew::prelude::*;
+use crate::data_grid::{CellContext, GridData, GridDataColumn, GridDataColumnProps};
+use crate::export::CellValue;
+use crate::tree::TreeData;
+
+/// A row whose values are kept as text so tests can hold invalid or mixed values
+#[derive(Debug, Clone, PartialEq)]
+pub struct Person {
+    pub id: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    pub age: String,
+    pub team: String,
+    pub note: String
+}
+
+/// A person with only an id and a name, the other values are set with the builder methods
+pub fn person(id: u32, name: &str) -> Person {
+    Person { id, parent: None, name: name.to_string(), age: String::new(), team: String::new(), note: String::new() }
+}
+
+/// People with ids counting from 0 and the given names
+pub fn people(names: &[&str]) -> Vec<Person> {
+    names.iter().enumerate().map(|(id, name)| person(id as u32, name)).collect()
+}
+
+impl Person {
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    pub fn age(mut self, age: &str) -> Self {
+        self.age = age.to_string();
+        self
+    }
+
+    pub fn team(mut self, team: &str) -> Self {
+        self.team = team.to_string();
+        self
+    }
+
+    pub fn note(mut self, note: &str) -> Self {
+        self.note = note.to_string();
+        self
+    }
+}
+
+/// Every column but `Id` is editable, ages must be whole numbers
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Field {
+    Id,
+    Name,
+    Age,
+    Team,
+    Note
+}
+
+impl GridDataColumn for Field {
+    type RowType = Person;
+    fn get_config(&self) -> GridDataColumnProps {
+        let (header_name, width) = match self {
+            Field::Id => ("Id", 60),
+            Field::Name => ("Name", 140),
+            Field::Age => ("Age", 70),
+            Field::Team => ("Team", 100),
+            Field::Note => ("Note, long", 200)
+        };
+        GridDataColumnProps {
+            header_name: header_name.to_string(),
+            width,
+            editable: *self != Field::Id,
+            sortable: true,
+            ..Default::default()
+        }
+    }
+    fn get_value(&self, row: &Person) -> String {
+        match self {
+            Field::Id => row.id.to_string(),
+            Field::Name => row.name.clone(),
+            Field::Age => row.age.clone(),
+            Field::Team => row.team.clone(),
+            Field::Note => row.note.clone()
+        }
+    }
+    /// Ids are rendered from the context rather than the row
+    fn render_cell(&self, row: &Person, ctx: &CellContext<u32>) -> Html {
+        match self {
+            Field::Id => html! { format!("#{}", ctx.row_id) },
+            _ => html! { self.get_value(row) }
+        }
+    }
+    fn validate(&self, _row: &Person, new_value: &str) -> Result<(), String> {
+        self.validate_import(new_value)
+    }
+    fn validate_import(&self, value: &str) -> Result<(), String> {
+        match self {
+            Field::Age if value.parse::<u32>().is_err() => Err("not a number".to_string()),
+            _ => Ok(())
+        }
+    }
+    /// Names and teams are always text, other values are numbers when they parse and null when empty
+    fn get_typed_value(&self, row: &Person) -> CellValue {
+        let value = self.get_value(row);
+        match self {
+            Field::Name | Field::Team => value.into(),
+            _ if value.is_empty() => CellValue::Null,
+            _ => value.parse::<f64>().map_or_else(|_| value.as_str().into(), CellValue::Number)
+        }
+    }
+    fn get_field_key(&self) -> String {
+        format!("{self:?}").to_lowercase()
+    }
+}
+
+impl GridData for Person {
+    type IdType = u32;
+    type ColumnType = Field;
+    fn get_id(&self) -> u32 {
+        self.id
+    }
+    fn suffixed_id(id: &u32, n: usize) -> Option<u32> {
+        Some(id * 10 + n as u32)
+    }
+}
+
+impl TreeData for Person {
+    fn get_parent_id(&self) -> Option<u32> {
+        self.parent
+    }
+    fn has_unloaded_children(&self) -> bool {
+        self.name == "lazy"
+    }
+}
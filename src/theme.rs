@@ -0,0 +1,111 @@
+/// Look of a `DataGrid`, set as CSS custom properties on the grid's container. Values are CSS values,
+/// so any colour or length syntax works and stylesheets can read the same `--yew-data-grid-*` properties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub font_family: String,
+    pub font_size: String,
+    pub text_color: String,
+    pub background: String,
+    pub header_background: String,
+    pub header_text_color: String,
+    pub border_color: String,
+    /// Width of the lines between rows
+    pub border_width: String,
+    /// Outlines of the active and edited cells and the selected page
+    pub accent_color: String,
+    pub selected_row_background: String,
+    /// Background of every other row, `None` keeps rows plain
+    pub row_stripe: Option<String>,
+    pub hover_background: Option<String>,
+    /// Background of menus, dialogs and toolbar buttons
+    pub surface_background: String,
+    /// Background of group rows and detail panels
+    pub group_background: String,
+    /// Horizontal padding of cells
+    pub cell_padding: String,
+    /// Indentation of each level of nested groups and tree rows
+    pub indent: String
+}
+
+impl Theme {
+    /// The bundled stylesheet's own look on a white background
+    pub fn light() -> Self {
+        Self {
+            font_family: "inherit".to_string(),
+            font_size: "inherit".to_string(),
+            text_color: "rgba(0, 0, 0, 0.88)".to_string(),
+            background: "#fff".to_string(),
+            header_background: "rgba(0, 0, 0, 0.02)".to_string(),
+            header_text_color: "inherit".to_string(),
+            border_color: "rgba(225, 225, 225, 1)".to_string(),
+            border_width: "1px".to_string(),
+            accent_color: "#1677ff".to_string(),
+            selected_row_background: "rgba(22, 119, 255, 0.08)".to_string(),
+            row_stripe: None,
+            hover_background: None,
+            surface_background: "#fff".to_string(),
+            group_background: "#fafafa".to_string(),
+            cell_padding: "10px".to_string(),
+            indent: "20px".to_string()
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            text_color: "rgba(255, 255, 255, 0.85)".to_string(),
+            background: "#141414".to_string(),
+            header_background: "rgba(255, 255, 255, 0.04)".to_string(),
+            border_color: "#303030".to_string(),
+            accent_color: "#1668dc".to_string(),
+            selected_row_background: "rgba(22, 104, 220, 0.2)".to_string(),
+            row_stripe: Some("rgba(255, 255, 255, 0.02)".to_string()),
+            hover_background: Some("rgba(255, 255, 255, 0.06)".to_string()),
+            surface_background: "#1f1f1f".to_string(),
+            group_background: "#1d1d1d".to_string(),
+            ..Self::light()
+        }
+    }
+
+    /// Declarations of the theme's custom properties, for the container's `style` attribute
+    pub fn css_variables(&self) -> String {
+        let transparent = "transparent".to_string();
+        [
+            ("font-family", &self.font_family),
+            ("font-size", &self.font_size),
+            ("text-color", &self.text_color),
+            ("background", &self.background),
+            ("header-background", &self.header_background),
+            ("header-text-color", &self.header_text_color),
+            ("border-color", &self.border_color),
+            ("border-width", &self.border_width),
+            ("accent-color", &self.accent_color),
+            ("selected-row-background", &self.selected_row_background),
+            ("row-stripe", self.row_stripe.as_ref().unwrap_or(&transparent)),
+            ("hover-background", self.hover_background.as_ref().unwrap_or(&transparent)),
+            ("surface-background", &self.surface_background),
+            ("group-background", &self.group_background),
+            ("cell-padding", &self.cell_padding),
+            ("indent", &self.indent)
+        ].iter().map(|(name, value)| format!("--yew-data-grid-{name}: {value};")).collect::<Vec<String>>().join(" ")
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_css_variables() {
+        let variables = Theme::dark().css_variables();
+        assert!(variables.starts_with("--yew-data-grid-font-family: inherit; --yew-data-grid-font-size: inherit;"));
+        assert!(variables.contains("--yew-data-grid-background: #141414;"));
+        assert!(variables.contains("--yew-data-grid-row-stripe: rgba(255, 255, 255, 0.02);"));
+        assert!(Theme::light().css_variables().contains("--yew-data-grid-hover-background: transparent;"));
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::{HashMap, HashSet};
+use crate::data_grid::GridData;
+
+/// Rows that form a hierarchy through their parent's id, shown with `Props::tree_data`
+pub trait TreeData: GridData {
+    /// `None` for top level rows, rows whose parent is not in `rows` are shown at the top level as well
+    fn get_parent_id(&self) -> Option<Self::IdType>;
+    /// Whether the row has children that are not in `rows` yet, expanding it calls `on_load_children`
+    fn has_unloaded_children(&self) -> bool {
+        false
+    }
+}
+
+/// Turns on tree data for `DataGrid`, the expand toggles and indentation go in `column`
+pub struct TreeDataMode<T: GridData> {
+    pub column: T::ColumnType,
+    get_parent_id: fn(&T) -> Option<T::IdType>,
+    has_unloaded_children: fn(&T) -> bool
+}
+
+impl<T: TreeData> TreeDataMode<T> {
+    pub fn new(column: T::ColumnType) -> Self {
+        Self { column, get_parent_id: T::get_parent_id, has_unloaded_children: T::has_unloaded_children }
+    }
+}
+
+impl<T: GridData> Clone for TreeDataMode<T>
+    where T::ColumnType: Clone
+{
+    fn clone(&self) -> Self {
+        Self {
+            column: self.column.clone(),
+            get_parent_id: self.get_parent_id,
+            has_unloaded_children: self.has_unloaded_children
+        }
+    }
+}
+
+impl<T: GridData> PartialEq for TreeDataMode<T>
+    where T::ColumnType: PartialEq
+{
+    // the functions always come from `T`'s `TreeData` impl
+    fn eq(&self, other: &Self) -> bool {
+        self.column == other.column
+    }
+}
+
+impl<T: GridData> TreeDataMode<T> {
+    pub fn has_unloaded_children(&self, row: &T) -> bool {
+        (self.has_unloaded_children)(row)
+    }
+}
+
+/// Where a row sits in the tree
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    pub depth: usize,
+    /// Some of the row's children are in `rows`
+    pub has_children: bool,
+    pub expandable: bool,
+    pub expanded: bool
+}
+
+impl TreeNode {
+    /// Expanded while its lazily loaded children have not arrived
+    pub fn loading(&self) -> bool {
+        self.expanded && !self.has_children
+    }
+}
+
+/// Rows in tree order, children follow their parent in `sorted_ids` order and only rows of expanded parents
+/// are included. Ancestors of the `matching_ids` stay visible and, while `filtering`, are expanded.
+pub fn tree_rows<T: GridData>(sorted_ids: &[T::IdType],
+                              matching_ids: &[T::IdType],
+                              rows: &[T],
+                              row_index_map: &HashMap<T::IdType, usize>,
+                              mode: &TreeDataMode<T>,
+                              expanded_ids: &HashSet<T::IdType>,
+                              filtering: bool) -> Vec<(T::IdType, TreeNode)> {
+    let parent_of = |id: &T::IdType| (mode.get_parent_id)(&rows[row_index_map[id]])
+        .filter(|parent| parent != id && row_index_map.contains_key(parent));
+    let mut visible: HashSet<T::IdType> = matching_ids.iter().cloned().collect();
+    let mut ancestors: HashSet<T::IdType> = HashSet::new();
+    for id in matching_ids {
+        let mut parent = parent_of(id);
+        // stops at ancestors already walked, which also guards against cycles
+        while let Some(id) = parent.filter(|p| ancestors.insert(p.clone())) {
+            visible.insert(id.clone());
+            parent = parent_of(&id);
+        }
+    }
+    let parents: HashSet<T::IdType> = sorted_ids.iter().filter_map(parent_of).collect();
+    let mut children: HashMap<Option<T::IdType>, Vec<&T::IdType>> = HashMap::new();
+    for id in sorted_ids.iter().filter(|id| visible.contains(*id)) {
+        children.entry(parent_of(id)).or_default().push(id);
+    }
+
+    let mut tree = Vec::with_capacity(visible.len());
+    let mut stack: Vec<(&T::IdType, usize)> = children.get(&None)
+        .map(|roots| roots.iter().rev().map(|id| (*id, 0)).collect())
+        .unwrap_or_default();
+    while let Some((id, depth)) = stack.pop() {
+        let has_children = parents.contains(id);
+        let expandable = has_children || mode.has_unloaded_children(&rows[row_index_map[id]]);
+        let expanded = expandable && (expanded_ids.contains(id) || (filtering && ancestors.contains(id)));
+        if expanded {
+            if let Some(child_ids) = children.get(&Some(id.clone())) {
+                stack.extend(child_ids.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+        tree.push((id.clone(), TreeNode { depth, has_children, expandable, expanded }));
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{person, Field, Person};
+
+    fn parts() -> Vec<Person> {
+        vec![person(1, "bike"), person(2, "frame").parent(1), person(3, "wheel").parent(1),
+             person(4, "spoke").parent(3), person(5, "lazy"), person(6, "orphan").parent(9)]
+    }
+
+    fn ids(tree: &[(u32, TreeNode)]) -> Vec<(u32, usize)> {
+        tree.iter().map(|(id, node)| (*id, node.depth)).collect()
+    }
+
+    #[test]
+    fn test_tree_rows_follow_expansion() {
+        let rows = parts();
+        let row_index_map: HashMap<u32, usize> = rows.iter().enumerate().map(|(i, row)| (row.id, i)).collect();
+        // sorted with the children before their parents, the tree keeps them under the parent
+        let sorted: Vec<u32> = vec![6, 4, 3, 2, 5, 1];
+        let mode = TreeDataMode::new(Field::Name);
+
+        let tree = tree_rows(&sorted, &sorted, &rows, &row_index_map, &mode, &HashSet::new(), false);
+        assert_eq!(ids(&tree), vec![(6, 0), (5, 0), (1, 0)]);
+        assert!(tree[1].1.expandable && !tree[1].1.has_children);
+        assert!(!tree[0].1.expandable);
+
+        let expanded: HashSet<u32> = [1, 3, 5].into_iter().collect();
+        let tree = tree_rows(&sorted, &sorted, &rows, &row_index_map, &mode, &expanded, false);
+        assert_eq!(ids(&tree), vec![(6, 0), (5, 0), (1, 0), (3, 1), (4, 2), (2, 1)]);
+        // expanded without children loaded yet
+        assert!(tree[1].1.loading());
+    }
+
+    #[test]
+    fn test_tree_rows_keep_ancestors_of_matches() {
+        let rows = parts();
+        let row_index_map: HashMap<u32, usize> = rows.iter().enumerate().map(|(i, row)| (row.id, i)).collect();
+        let sorted: Vec<u32> = (1..=6).collect();
+        let mode = TreeDataMode::new(Field::Name);
+
+        let tree = tree_rows(&sorted, &[4], &rows, &row_index_map, &mode, &HashSet::new(), true);
+        assert_eq!(ids(&tree), vec![(1, 0), (3, 1), (4, 2)]);
+    }
+}
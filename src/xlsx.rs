@@ -0,0 +1,256 @@
+use std::fmt::Write;
+use std::hash::Hash;
+use crate::data_grid::GridDataColumn;
+use crate::error::GridError;
+use crate::export::{CellValue, ExportScope, ExportView};
+
+pub const XLSX_MIME_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XlsxOptions {
+    pub scope: ExportScope,
+    /// Name of the worksheet tab, checked with `validate_sheet_name`
+    pub sheet_name: String,
+    pub file_name: String
+}
+
+impl Default for XlsxOptions {
+    fn default() -> Self {
+        Self { scope: ExportScope::All, sheet_name: "Sheet1".to_string(), file_name: "export.xlsx".to_string() }
+    }
+}
+
+impl<I: Hash + Eq, T, U: GridDataColumn<RowType=T>> ExportView<I, T, U> {
+    pub fn to_xlsx(&self, options: &XlsxOptions) -> Result<Vec<u8>, GridError> {
+        to_xlsx(self.rows(options.scope).into_iter(), &self.columns, options)
+    }
+}
+
+/// Writes rows to a single sheet workbook with a bold, frozen header row. Column widths follow
+/// `GridDataColumnProps::width` and the columns' typed values are kept as numbers and booleans.
+/// Fails when Excel would refuse to open the workbook because of its sheet name.
+pub fn to_xlsx<'a, T: 'a, U: GridDataColumn<RowType=T>>(rows: impl Iterator<Item=&'a T>,
+                                                        columns: &[U],
+                                                        options: &XlsxOptions) -> Result<Vec<u8>, GridError> {
+    validate_sheet_name(&options.sheet_name)?;
+    let mut zip = ZipWriter::default();
+    zip.add_file("[Content_Types].xml", CONTENT_TYPES.as_bytes());
+    zip.add_file("_rels/.rels", ROOT_RELS.as_bytes());
+    zip.add_file("xl/workbook.xml", workbook_xml(&options.sheet_name).as_bytes());
+    zip.add_file("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes());
+    zip.add_file("xl/styles.xml", STYLES.as_bytes());
+    zip.add_file("xl/worksheets/sheet1.xml", sheet_xml(rows, columns).as_bytes());
+    Ok(zip.finish())
+}
+
+/// Excel's rules for worksheet names: 1 to 31 characters, none of `[ ] : * ? / \` and no apostrophe at
+/// either end
+pub fn validate_sheet_name(name: &str) -> Result<(), GridError> {
+    let valid = (1..=31).contains(&name.chars().count())
+        && !name.contains(['[', ']', ':', '*', '?', '/', '\\'])
+        && !name.starts_with('\'')
+        && !name.ends_with('\'');
+    if valid {
+        Ok(())
+    } else {
+        Err(GridError::InvalidSheetName(name.to_string()))
+    }
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+// style 0 is the default, style 1 the header: bold on a light grey fill with a bottom border
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="3"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill><fill><patternFill patternType="solid"><fgColor rgb="FFF2F2F2"/><bgColor indexed="64"/></patternFill></fill></fills><borders count="2"><border><left/><right/><top/><bottom/><diagonal/></border><border><left/><right/><top/><bottom style="thin"><color rgb="FFBFBFBF"/></bottom><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="2" borderId="1" xfId="0" applyFont="1" applyFill="1" applyBorder="1"/></cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#;
+
+fn workbook_xml(sheet_name: &str) -> String {
+    format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            xml_escape(sheet_name))
+}
+
+fn sheet_xml<'a, T: 'a, U: GridDataColumn<RowType=T>>(rows: impl Iterator<Item=&'a T>, columns: &[U]) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews>"#);
+    if !columns.is_empty() {
+        xml.push_str("<cols>");
+        for (i, column) in columns.iter().enumerate() {
+            // Excel measures widths in characters of the default font, about 7 pixels each
+            let width = (column.get_config().width.max(0) as f64 / 7.0 * 100.0).round() / 100.0;
+            let _ = write!(xml, r#"<col min="{n}" max="{n}" width="{width}" customWidth="1"/>"#, n = i + 1);
+        }
+        xml.push_str("</cols>");
+    }
+    xml.push_str(r#"<sheetData><row r="1">"#);
+    for (i, column) in columns.iter().enumerate() {
+        let _ = write!(xml, r#"<c r="{}1" s="1" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                       column_name(i), xml_escape(&column.get_config().header_name));
+    }
+    xml.push_str("</row>");
+    for (r, row) in rows.enumerate() {
+        let line = r + 2;
+        let _ = write!(xml, r#"<row r="{line}">"#);
+        for (i, column) in columns.iter().enumerate() {
+            let cell = format!("{}{line}", column_name(i));
+            let _ = match column.get_typed_value(row) {
+                CellValue::Null => Ok(()),
+                CellValue::Bool(value) => write!(xml, r#"<c r="{cell}" t="b"><v>{}</v></c>"#, value as u8),
+                CellValue::Number(value) if value.is_finite() => write!(xml, r#"<c r="{cell}"><v>{value}</v></c>"#),
+                CellValue::Number(_) => Ok(()),
+                CellValue::Text(value) => write!(xml, r#"<c r="{cell}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                                                 xml_escape(&value))
+            };
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+/// Spreadsheet column letters, 0 is `A`, 25 is `Z` and 26 is `AA`
+fn column_name(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        name.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.iter().rev().map(|c| *c as char).collect()
+}
+
+/// Escapes markup characters and drops the control characters XML 1.0 cannot hold
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Minimal zip archive writer, entries are stored uncompressed which every spreadsheet app accepts
+#[derive(Default)]
+struct ZipWriter {
+    data: Vec<u8>,
+    central_directory: Vec<u8>,
+    entries: u16
+}
+
+impl ZipWriter {
+    fn add_file(&mut self, name: &str, content: &[u8]) {
+        let offset = self.data.len() as u32;
+        let crc = crc32(content);
+        let size = content.len() as u32;
+        // version, flags (bit 11: utf-8 names), method (stored), time, date (1980-01-01)
+        let header_fields = |out: &mut Vec<u8>| {
+            for value in [20u16, 0x0800, 0, 0, 0x0021] {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            for value in [crc, size, size] {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+        };
+
+        self.data.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header_fields(&mut self.data);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(content);
+
+        self.central_directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.central_directory.extend_from_slice(&20u16.to_le_bytes());
+        header_fields(&mut self.central_directory);
+        // comment length, disk number, internal and external attributes
+        for value in [0u16, 0, 0, 0, 0] {
+            self.central_directory.extend_from_slice(&value.to_le_bytes());
+        }
+        self.central_directory.extend_from_slice(&offset.to_le_bytes());
+        self.central_directory.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let directory_offset = self.data.len() as u32;
+        let directory_size = self.central_directory.len() as u32;
+        self.data.append(&mut self.central_directory);
+        self.data.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        for value in [0u16, 0, self.entries, self.entries] {
+            self.data.extend_from_slice(&value.to_le_bytes());
+        }
+        self.data.extend_from_slice(&directory_size.to_le_bytes());
+        self.data.extend_from_slice(&directory_offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{person, Field};
+
+    #[test]
+    fn test_sheet_xml() {
+        let rows = [person(1, "<Ada & Co>").age("12.5"), person(2, "Grace").age("-3")];
+        let xml = sheet_xml(rows.iter(), &[Field::Name, Field::Age]);
+        assert!(xml.contains(r#"<col min="1" max="1" width="20" customWidth="1"/><col min="2" max="2" width="10" customWidth="1"/>"#));
+        assert!(xml.contains(r#"<c r="A1" s="1" t="inlineStr"><is><t xml:space="preserve">Name</t></is></c>"#));
+        assert!(xml.contains(r#"<c r="A2" t="inlineStr"><is><t xml:space="preserve">&lt;Ada &amp; Co&gt;</t></is></c><c r="B2"><v>12.5</v></c>"#));
+        assert!(xml.contains(r#"<c r="B3"><v>-3</v></c>"#));
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(column_name(702), "AAA");
+    }
+
+    #[test]
+    fn test_zip_layout() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        let bytes = to_xlsx([person(1, "Ada").age("1")].iter(), &[Field::Name, Field::Age], &XlsxOptions::default()).unwrap();
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        let end = &bytes[bytes.len() - 22..];
+        assert_eq!(&end[..4], b"PK\x05\x06");
+        // six parts: content types, two relationship files, workbook, styles and the sheet
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 6);
+        let directory_offset = u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize;
+        assert_eq!(&bytes[directory_offset..directory_offset + 4], b"PK\x01\x02");
+    }
+
+    #[test]
+    fn test_validate_sheet_name() {
+        assert_eq!(validate_sheet_name("Q1 & Q2 (draft)"), Ok(()));
+        assert_eq!(validate_sheet_name(&"é".repeat(31)), Ok(()));
+        for name in ["", &"x".repeat(32), "a/b", "[1]", "what?", "'quoted'"] {
+            assert_eq!(validate_sheet_name(name), Err(GridError::InvalidSheetName(name.to_string())));
+        }
+        let options = XlsxOptions { sheet_name: "a:b".to_string(), ..Default::default() };
+        assert!(to_xlsx([person(1, "Ada")].iter(), &[Field::Name], &options).is_err());
+    }
+}